@@ -1,3 +1,5 @@
+from typing import Iterator, Literal
+
 class GeneratedNumber:
     @property
     def direction(self) -> str: ...
@@ -5,12 +7,49 @@ class GeneratedNumber:
     @property
     def pattern(self) -> str: ...
 
+    @property
+    def quasi_area(self) -> int: ...
+
     @property
     def largest_dimension(self) -> int: ...
     
     @property
     def num_points(self) -> int: ...
 
+    @property
+    def stopped_early(self) -> bool: ...
+
+    @property
+    def optimality(self) -> Literal["optimal", "optimal-in-bounds", "bounded", "heuristic"]: ...
+
+    @property
+    def lower_bound(self) -> int | None:
+        """No pattern in the search space costs less than this, or None if the search space has no patterns at all."""
+
+    @property
+    def within_factor(self) -> float | None:
+        """The pattern costs at most this many times as much as the best one, going by lower_bound."""
+
+    @property
+    def seed(self) -> int | None:
+        """Seed of the beam search run that found the best pattern, which reproduces it with no restarts."""
+
+class PatternReport:
+    @property
+    def overlapping_segments(self) -> list[tuple[int, int, str]]: ...
+
+    @property
+    def back_angles(self) -> list[int]: ...
+
+    @property
+    def bounds(self) -> tuple[int, int, int]: ...
+
+    @property
+    def fits_in_bounds(self) -> bool: ...
+
+    @property
+    def is_valid(self) -> bool: ...
+
 def generate_number_pattern_beam(
     target: int | tuple[int, int],
     q_size: int = 8,
@@ -19,10 +58,159 @@ def generate_number_pattern_beam(
     carryover: int = 25,
     trim_larger: bool = True,
     allow_fractions: bool = False,
+    ranking: str = "length,distance,num-points",
+    stochastic: bool = False,
+    restarts: int = 0,
+    seed: int = 0,
 ) -> GeneratedNumber | None: ...
 
 def generate_number_pattern_astar(
     target: int | tuple[int, int],
     trim_larger: bool = True,
     allow_fractions: bool = False,
-) -> GeneratedNumber | None: ...
+    q_size: int | None = None,
+    r_size: int | None = None,
+    s_size: int | None = None,
+) -> GeneratedNumber | None:
+    """Unbounded unless a size is given, in which case missing sizes default to 8."""
+
+def generate_number_pattern(
+    target: int | tuple[int, int],
+    algorithm: Literal["beam", "astar", "exhaustive", "bidirectional", "smastar"] = "beam",
+    *,
+    q_size: int | None = None,
+    r_size: int | None = None,
+    s_size: int | None = None,
+    carryover: int = 25,
+    ranking: str = "length,distance,num-points",
+    stochastic: bool = False,
+    restarts: int = 0,
+    seed: int = 0,
+    max_length: int | None = None,
+    backward_depth: int = 6,
+    max_nodes: int = 1_000_000,
+    trim_larger: bool = True,
+    allow_fractions: bool = False,
+    epsilon: float = 1.0,
+    transpositions: bool = True,
+    threads: int = 1,
+    timeout: float | None = None,
+    max_expanded: int | None = None,
+    max_frontier: int | None = None,
+    objective: str = "quasi-area",
+    pareto: bool = False,
+    count: int = 1,
+) -> GeneratedNumber | None:
+    """Missing sizes default to 8, but only beam search is bounded if no size is given at all."""
+
+def generate_number_patterns(
+    target: int | tuple[int, int],
+    algorithm: Literal["beam", "astar", "exhaustive", "bidirectional", "smastar"] = "beam",
+    *,
+    q_size: int | None = None,
+    r_size: int | None = None,
+    s_size: int | None = None,
+    carryover: int = 25,
+    ranking: str = "length,distance,num-points",
+    stochastic: bool = False,
+    restarts: int = 0,
+    seed: int = 0,
+    max_length: int | None = None,
+    backward_depth: int = 6,
+    max_nodes: int = 1_000_000,
+    trim_larger: bool = True,
+    allow_fractions: bool = False,
+    epsilon: float = 1.0,
+    transpositions: bool = True,
+    threads: int = 1,
+    timeout: float | None = None,
+    max_expanded: int | None = None,
+    max_frontier: int | None = None,
+    objective: str = "quasi-area",
+    pareto: bool = False,
+    count: int = 1,
+) -> list[GeneratedNumber]:
+    """Like generate_number_pattern, but returns every kept pattern: the whole Pareto front if pareto is set, or the
+    best count distinct patterns, best first. Dominated paths aren't skipped when count is above one, so that's slower."""
+
+class PatternStream(Iterator[tuple[GeneratedNumber, float]]):
+    def __next__(self) -> tuple[GeneratedNumber, float]: ...
+
+def stream_number_patterns(
+    target: int | tuple[int, int],
+    algorithm: Literal["beam", "astar", "exhaustive", "bidirectional", "smastar"] = "beam",
+    *,
+    q_size: int | None = None,
+    r_size: int | None = None,
+    s_size: int | None = None,
+    carryover: int = 25,
+    ranking: str = "length,distance,num-points",
+    stochastic: bool = False,
+    restarts: int = 0,
+    seed: int = 0,
+    max_length: int | None = None,
+    backward_depth: int = 6,
+    max_nodes: int = 1_000_000,
+    trim_larger: bool = True,
+    allow_fractions: bool = False,
+    epsilon: float = 1.0,
+    transpositions: bool = True,
+    threads: int = 1,
+    timeout: float | None = None,
+    max_expanded: int | None = None,
+    max_frontier: int | None = None,
+    objective: str = "quasi-area",
+    pareto: bool = False,
+    count: int = 1,
+) -> PatternStream:
+    """Yields each new smallest pattern along with the seconds elapsed since the search started."""
+
+def find_minimum_bounds(
+    target: int | tuple[int, int],
+    by: Literal["quasi-area", "largest-dimension"] = "quasi-area",
+    max_carryover: int = 400,
+    *,
+    carryover: int = 25,
+    ranking: str = "length,distance,num-points",
+    stochastic: bool = False,
+    restarts: int = 0,
+    seed: int = 0,
+    trim_larger: bool = True,
+    allow_fractions: bool = False,
+    epsilon: float = 1.0,
+    transpositions: bool = True,
+    threads: int = 1,
+    timeout: float | None = None,
+    max_expanded: int | None = None,
+    max_frontier: int | None = None,
+    objective: str = "quasi-area",
+) -> tuple[GeneratedNumber, tuple[int, int, int], int] | None:
+    """Finds the smallest bounds that beam search can fit the target into. Returns the pattern, along with the bounds
+    and carryover of the beam search that found it. The timeout covers the whole search."""
+
+def check_feasibility(
+    target: int | tuple[int, int],
+    *,
+    q_size: int = 8,
+    r_size: int = 8,
+    s_size: int = 8,
+    transpositions: bool = True,
+    timeout: float | None = None,
+    max_expanded: int | None = None,
+) -> tuple[bool | None, GeneratedNumber | None, dict[str, int]]:
+    """Searches every path in the bounds to either find a pattern that fits or prove that none does. Returns whether
+    one fits (None if the search stopped early), a pattern that does, and how many paths each pruning rule (overlap,
+    out-of-bounds, segments-left, dominated) ruled out. Every rotation of the pattern is tried."""
+
+def decode_number_pattern(
+    direction: str,
+    pattern: str,
+) -> int | tuple[int, int]: ...
+
+def validate_pattern(
+    direction: str,
+    pattern: str,
+    q_size: int = 8,
+    r_size: int = 8,
+    s_size: int = 8,
+) -> PatternReport: ...
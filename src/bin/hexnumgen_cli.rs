@@ -1,8 +1,12 @@
-use std::str::FromStr;
+use std::{str::FromStr, sync::Arc, thread, time::Duration};
 
 use anyhow::Error;
-use clap::Parser;
-use hexnumgen::{generate_number_pattern_astar, generate_number_pattern_beam, Bounds, GeneratedNumber};
+use clap::{builder::RangedU64ValueParser, Parser};
+use hexnumgen::{
+    decode_number_pattern, validate_pattern, Algorithm, Bounds, BoundsMetric, Collect, Direction, Feasibility,
+    FeasibilityCheck, FeasibilityReport, GeneratedNumber, GeneratorConfig, Improvement, MinimumBounds,
+    MinimumBoundsSearch, Objective, PatternReport, Ranking, SearchLimits, Weighted,
+};
 use num_rational::Ratio;
 
 #[derive(Clone)]
@@ -43,7 +47,16 @@ impl FromStr for ParsedRatio {
 #[derive(Parser)]
 struct Cli {
     /// Target number to generate a literal for
-    target: ParsedRatio,
+    #[arg(required_unless_present_any = ["decode", "validate"])]
+    target: Option<ParsedRatio>,
+
+    /// Print the number drawn by an existing pattern instead of generating one, eg. `--decode SOUTH_EAST aqaaweaq`
+    #[arg(short, long, num_args = 2, value_names = ["DIRECTION", "PATTERN"], conflicts_with = "target")]
+    decode: Option<Vec<String>>,
+
+    /// Check an existing pattern for overlapping segments, back angles and size instead of generating one
+    #[arg(long, num_args = 2, value_names = ["DIRECTION", "PATTERN"], conflicts_with_all = ["target", "decode"])]
+    validate: Option<Vec<String>>,
 
     /// Whether to make the target negative
     #[arg(short, long)]
@@ -53,9 +66,49 @@ struct Cli {
     #[arg(short, long)]
     astar: bool,
 
-    /// Maximum size of the generated pattern (overridden by q_size, r_size, and/or s_size if set)
-    #[arg(long, default_value_t = 8)]
-    size: u32,
+    /// Whether to search every possible path instead of using beam search (exact, but only practical for small targets)
+    #[arg(short, long, conflicts_with = "astar")]
+    exhaustive: bool,
+
+    /// Whether to search forwards from the prefix and backwards from the target at the same time instead of using beam
+    /// search (much faster than A* with --keep-larger, but not otherwise)
+    #[arg(short, long, conflicts_with_all = ["astar", "exhaustive"])]
+    bidirectional: bool,
+
+    /// Whether to use memory-bounded A* (SMA*), which forgets the worst queued paths instead of running out of memory
+    #[arg(long, conflicts_with_all = ["astar", "exhaustive", "bidirectional"])]
+    sma: bool,
+
+    /// Find the smallest bounds that beam search can fit the target into, by quasi-area or largest-dimension, instead of
+    /// searching in the given size
+    #[arg(long, value_name = "METRIC", conflicts_with_all = ["astar", "exhaustive", "bidirectional", "sma"])]
+    min_bounds: Option<BoundsMetric>,
+
+    /// Search every path in the given size to either find a pattern that fits or prove that none does, and print which
+    /// rules were used to rule paths out
+    #[arg(long, conflicts_with_all = ["astar", "exhaustive", "bidirectional", "sma", "min_bounds"])]
+    feasibility: bool,
+
+    /// Largest carryover tried on each shape with --min-bounds before moving on
+    #[arg(long, default_value_t = 400, requires = "min_bounds")]
+    max_carryover: usize,
+
+    /// Most paths kept in memory at once with --sma before the worst queued ones are forgotten
+    #[arg(long, default_value_t = 1_000_000, requires = "sma")]
+    max_nodes: usize,
+
+    /// Number of angles searched backwards from the target with --bidirectional
+    #[arg(long, default_value_t = 6, requires = "bidirectional")]
+    backward_depth: usize,
+
+    /// Longest pattern to consider with --exhaustive, in segments including the prefix
+    #[arg(long, requires = "exhaustive")]
+    max_length: Option<usize>,
+
+    /// Maximum size of the generated pattern (overridden by q_size, r_size, and/or s_size if set). Defaults to 8 for beam
+    /// search and validation, while the other algorithms are unbounded unless a size is given
+    #[arg(long)]
+    size: Option<u32>,
 
     /// Maximum size of the generated pattern in the q direction (northeast/southwest)
     #[arg(short, long)]
@@ -73,6 +126,26 @@ struct Cli {
     #[arg(short, long, default_value_t = 25)]
     carryover: usize,
 
+    /// Keys that beam search keeps the best paths by, in order: length, distance, log-distance, num-points, quasi-area,
+    /// largest-dimension or random, each with an optional quota like `length=10,distance,random=5` (defaults to
+    /// carryover)
+    #[arg(long, default_value = "length,distance,num-points")]
+    ranking: Ranking,
+
+    /// Sample the paths kept by each ranking with beam search, favouring better ranks, instead of keeping the best ones
+    #[arg(long)]
+    stochastic: bool,
+
+    /// Number of extra beam search runs, each seeded with the next seed, keeping the best result (only with --stochastic
+    /// or a random ranking, since otherwise every run is the same)
+    #[arg(long, default_value_t = 0)]
+    restarts: usize,
+
+    /// Seed for beam search's random choices. The seed that found the best pattern is printed, and reproduces it with
+    /// no restarts
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
     /// Whether generated paths larger than the target value should be kept or discarded (generates slower but may give better results)
     #[arg(short, long)]
     keep_larger: bool,
@@ -80,26 +153,244 @@ struct Cli {
     /// If fractional targets and intermediate values should be allowed
     #[arg(short, long, default_value_t = false)]
     fractions: bool,
+
+    /// Trade quality for speed by skipping paths that can't beat the best one by more than this factor, and weighting the
+    /// A* heuristic by it. The result costs at most this many times as much as the best pattern
+    #[arg(long, default_value_t = 1.0)]
+    epsilon: f64,
+
+    /// Keep expanding paths that are dominated by an earlier path with the same value and end segment, eg. to check that
+    /// skipping them doesn't change results
+    #[arg(long)]
+    no_transpositions: bool,
+
+    /// Number of threads used to expand paths with beam search or A* (0 uses every available core)
+    #[arg(short = 'j', long, default_value_t = 1)]
+    threads: usize,
+
+    /// Stop searching after this many seconds and print the best pattern found so far
+    #[arg(short, long)]
+    timeout: Option<f64>,
+
+    /// Stop searching after expanding this many paths
+    #[arg(long)]
+    max_expanded: Option<usize>,
+
+    /// Stop searching once this many paths are waiting to be expanded. Ignored by --exhaustive and --feasibility, which
+    /// search depth-first
+    #[arg(long)]
+    max_frontier: Option<usize>,
+
+    /// What counts as the smallest pattern: a metric (length, quasi-area, largest-dimension, num-points) or a weighted
+    /// sum like `length=2,quasi-area=1`
+    #[arg(short, long, default_value = "quasi-area")]
+    objective: Weighted,
+
+    /// Print each smaller pattern to stderr as soon as it's found
+    #[arg(short, long)]
+    progress: bool,
+
+    /// Print every pattern that isn't beaten in all of length, quasi-area, num-points and largest-dimension by another
+    /// pattern, instead of only the best one
+    #[arg(long)]
+    pareto: bool,
+
+    /// Print this many of the best distinct patterns, best first (rotations of the same shape count once). Dominated
+    /// paths aren't skipped when this is above one, so searches are slower
+    #[arg(
+        long,
+        default_value_t = 1,
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..),
+        conflicts_with = "pareto"
+    )]
+    count: usize,
+}
+
+fn print_report(report: &PatternReport) {
+    for segment in &report.overlapping_segments {
+        let root = segment.root();
+        println!("overlapping segment at ({}, {}) facing {}", root.q(), root.r(), segment.direction());
+    }
+    for index in &report.back_angles {
+        println!("back angle at index {index}");
+    }
+
+    let bounds = report.bounds;
+    let fits = if report.fits_in_bounds { "fits" } else { "does not fit" };
+    println!("bounds q={} r={} s={} ({fits} in the given size)", bounds.q(), bounds.r(), bounds.s());
+    println!("{}", if report.is_valid() { "valid" } else { "invalid" });
 }
 
 fn main() -> Result<(), String> {
     let cli = Cli::parse();
 
-    let target = if cli.negative { -cli.target.0 } else { cli.target.0 };
+    if let Some(decode) = cli.decode {
+        let value = decode[0]
+            .parse::<Direction>()
+            .and_then(|direction| decode_number_pattern(direction, &decode[1]))
+            .map_err(|err| err.to_string())?;
+
+        println!("{value}");
+        return Ok(());
+    }
+
+    let size = cli.size.unwrap_or(8);
+    let bounds = Bounds::new(cli.q_size.unwrap_or(size), cli.r_size.unwrap_or(size), cli.s_size.unwrap_or(size));
+    let sized = [cli.size, cli.q_size, cli.r_size, cli.s_size].iter().any(Option::is_some);
+
+    if let Some(validate) = cli.validate {
+        let report = validate[0]
+            .parse::<Direction>()
+            .and_then(|direction| validate_pattern(direction, &validate[1], bounds))
+            .map_err(|err| err.to_string())?;
+
+        print_report(&report);
+        return Ok(());
+    }
+
+    let target = cli.target.unwrap().0;
+    let target = if cli.negative { -target } else { target };
     if !cli.fractions && !target.is_integer() {
         return Err("Tried to generate non-integer number without enabling fractions".into());
     }
 
-    let bounds =
-        Bounds::new(cli.q_size.unwrap_or(cli.size), cli.r_size.unwrap_or(cli.size), cli.s_size.unwrap_or(cli.size));
-
-    let GeneratedNumber { direction, pattern, .. } = if cli.astar {
-        generate_number_pattern_astar(target, !cli.keep_larger, cli.fractions)
+    let (algorithm, search_bounds) = if cli.astar {
+        (Algorithm::AStar, sized.then_some(bounds))
+    } else if cli.exhaustive {
+        (Algorithm::Exhaustive, sized.then_some(bounds))
+    } else if cli.bidirectional {
+        (Algorithm::Bidirectional, sized.then_some(bounds))
+    } else if cli.sma {
+        (Algorithm::SmaStar, sized.then_some(bounds))
     } else {
-        generate_number_pattern_beam(target, bounds, cli.carryover, !cli.keep_larger, cli.fractions)
+        (Algorithm::Beam, Some(bounds))
+    };
+    let config = GeneratorConfig {
+        bounds: search_bounds,
+        carryover: cli.carryover,
+        ranking: cli.ranking,
+        stochastic: cli.stochastic,
+        restarts: cli.restarts,
+        seed: cli.seed,
+        max_length: cli.max_length,
+        backward_depth: cli.backward_depth,
+        max_nodes: cli.max_nodes,
+        trim_larger: !cli.keep_larger,
+        allow_fractions: cli.fractions,
+        epsilon: cli.epsilon,
+        transpositions: !cli.no_transpositions,
+        threads: match cli.threads {
+            0 => thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        },
+        limits: SearchLimits {
+            time: cli.timeout.map(Duration::from_secs_f64),
+            max_expanded: cli.max_expanded,
+            max_frontier: cli.max_frontier,
+            ..SearchLimits::default()
+        },
+        objective: Arc::new(cli.objective.clone()),
+        collect: match (cli.pareto, cli.count) {
+            (true, _) => Collect::ParetoFront,
+            (false, 1) => Collect::Best,
+            (false, count) => Collect::Top(count),
+        },
+        ..GeneratorConfig::new(target)
+    };
+
+    if cli.feasibility {
+        let FeasibilityReport { feasibility, expanded, pruned } = FeasibilityCheck::new(config, bounds).run();
+        for (rule, count) in pruned {
+            eprintln!("{rule}: ruled out {count} paths");
+        }
+        eprintln!("expanded {expanded} paths");
+
+        let path = match feasibility {
+            Feasibility::Feasible(path) => path,
+            Feasibility::Infeasible => {
+                let size = format!("{} {} {}", bounds.q(), bounds.r(), bounds.s());
+                return Err(format!("No pattern for {target} fits in bounds {size}"));
+            }
+            Feasibility::Unknown(reason) => {
+                return Err(format!("Search stopped early ({reason}), a pattern may still fit"));
+            }
+        };
+        let GeneratedNumber { direction, pattern, .. } = path.into();
+        println!("{direction} {pattern}");
+        return Ok(());
+    }
+
+    if let Some(by) = cli.min_bounds {
+        let MinimumBounds { best, attempts, stop_reason } =
+            MinimumBoundsSearch::new(config, by, cli.max_carryover).run();
+        if let Some(reason) = stop_reason {
+            eprintln!("Search stopped early ({reason}), smaller bounds may work");
+        }
+        let Some((path, attempt)) = best else {
+            return Err(format!("No pattern found for {target}"));
+        };
+
+        let (bounds, searched) = (path.bounds(), attempt.bounds);
+        eprintln!(
+            "bounds {} {} {} (searched {} {} {} with carryover {}, {} attempts)",
+            bounds.q(),
+            bounds.r(),
+            bounds.s(),
+            searched.q(),
+            searched.r(),
+            searched.s(),
+            attempt.carryover,
+            attempts.len()
+        );
+        let GeneratedNumber { direction, pattern, .. } = path.into();
+        println!("{direction} {pattern}");
+        return Ok(());
+    }
+
+    let outcome = algorithm.generator(config).run_with(&mut |improvement| {
+        if cli.progress {
+            let Improvement { path, bounds, elapsed } = improvement;
+            let (direction, pattern) = (path.starting_direction(), path.pattern());
+            let cost = cli.objective.cost(path);
+            eprintln!(
+                "[{:.2}s] {direction} {pattern} (cost {cost}, quasi-area {})",
+                elapsed.as_secs_f64(),
+                bounds.quasi_area()
+            );
+        }
+    });
+    if let Some(reason) = outcome.stop_reason {
+        eprintln!("Search stopped early ({reason}), a smaller pattern may exist");
+    }
+    match (outcome.lower_bound, outcome.within_factor(&cli.objective)) {
+        (Some(lower_bound), Some(factor)) if factor > 1.0 => {
+            eprintln!("{} (lower bound {lower_bound}, within {factor:.2}x of optimal)", outcome.optimality)
+        }
+        (Some(lower_bound), _) => eprintln!("{} (lower bound {lower_bound})", outcome.optimality),
+        (None, _) => eprintln!("{}", outcome.optimality),
+    }
+
+    if let Some(seed) = outcome.seed.filter(|_| cli.stochastic || cli.restarts > 0) {
+        eprintln!("seed {seed}");
     }
-    .ok_or_else(|| format!("No pattern found for {target}"))?;
 
-    println!("{direction} {pattern}");
+    if outcome.solutions.is_empty() {
+        return Err(format!("No pattern found for {target}"));
+    }
+
+    if cli.pareto {
+        for path in outcome.solutions {
+            let length = path.len();
+            let GeneratedNumber { direction, pattern, quasi_area, num_points, largest_dimension, .. } = path.into();
+            println!(
+                "{direction} {pattern} (length {length}, quasi-area {quasi_area}, points {num_points}, largest dimension {largest_dimension})"
+            );
+        }
+    } else {
+        for path in outcome.solutions {
+            let GeneratedNumber { direction, pattern, .. } = path.into();
+            println!("{direction} {pattern}");
+        }
+    }
     Ok(())
 }
@@ -1,5 +1,5 @@
 use clap::Parser;
-use hexnumgen::{generate_number_pattern_astar, Direction, GeneratedNumber};
+use hexnumgen::{generate_number_pattern_astar, Direction, GeneratedNumber, Optimality};
 use num_rational::Ratio;
 use num_traits::Zero;
 use rand::{seq::SliceRandom, thread_rng};
@@ -23,21 +23,24 @@ fn n_groups<T>(mut values: Vec<T>, n: usize) -> Vec<Vec<T>> {
     groups
 }
 
-fn worker(targets: Vec<Ratio<i64>>, tx: Sender<HashMap<Ratio<i64>, (String, String)>>) {
+type Entry = ((String, String), (Optimality, Option<u64>));
+
+fn worker(targets: Vec<Ratio<i64>>, tx: Sender<HashMap<Ratio<i64>, Entry>>) {
     let mut data = HashMap::new();
     let re = Regex::new(r"^aqaa").unwrap();
 
     for (i, &target) in targets.iter().enumerate() {
         println!("{}/{}", i + 1, targets.len());
 
-        let GeneratedNumber { direction, pattern, .. } = generate_number_pattern_astar(target, false, false).unwrap();
+        let GeneratedNumber { direction, pattern, optimality, lower_bound, .. } =
+            generate_number_pattern_astar(target, false, false).unwrap();
 
         if !target.is_zero() {
             let negative_pattern = re.replace(&pattern, "dedd").to_string();
-            data.insert(-target, (Direction::NorthEast.to_string(), negative_pattern));
+            data.insert(-target, ((Direction::NorthEast.to_string(), negative_pattern), (optimality, lower_bound)));
         }
 
-        data.insert(target, (direction, pattern));
+        data.insert(target, ((direction, pattern), (optimality, lower_bound)));
     }
 
     tx.send(data).unwrap();
@@ -55,7 +58,7 @@ fn main() {
     let mut all_targets = Vec::from_iter((0..=max).map(|n| (n as i64).into()));
     all_targets.shuffle(&mut thread_rng());
 
-    let cpus = thread::available_parallelism().unwrap().get() - 1;
+    let cpus = thread::available_parallelism().unwrap().get().saturating_sub(1).max(1);
 
     let (tx, rx) = mpsc::channel();
 
@@ -66,9 +69,16 @@ fn main() {
     drop(tx);
 
     let mut all_data = HashMap::new();
+    let mut certificates = HashMap::new();
     while let Ok(data) = rx.recv() {
-        all_data.extend(data);
+        for (target, (pattern, (optimality, lower_bound))) in data {
+            // json keys have to be strings
+            all_data.insert(target.to_string(), pattern);
+            certificates.insert(target.to_string(), (optimality.to_string(), lower_bound));
+        }
     }
 
     fs::write(format!("numbers_{max}.json"), serde_json::to_string(&all_data).unwrap()).unwrap();
+    // kept separate so the pattern table stays in the format mods already read
+    fs::write(format!("numbers_{max}_certificates.json"), serde_json::to_string(&certificates).unwrap()).unwrap();
 }
@@ -1,3 +1,6 @@
+//! The error type shared by the whole crate.
+
+use num_rational::Ratio;
 use thiserror::Error;
 
 use crate::hex_math::{Angle, Segment};
@@ -6,10 +9,22 @@ use crate::hex_math::{Angle, Segment};
 pub enum HexError {
     #[error("invalid character `{0}`")]
     InvalidChar(char),
+    #[error("invalid direction `{0}`")]
+    InvalidDirection(String),
     #[error("invalid angle `{0:?}`")]
     InvalidAngle(Angle),
     #[error("invalid angle `{0:?}` for number `{1}`")]
     InvalidAngleForNumber(Angle, u32),
     #[error("segment `{0:?}` already exists in path")]
     SegmentAlreadyExists(Segment),
+    #[error("segment `{0:?}` is outside the search bounds")]
+    SegmentOutOfBounds(Segment),
+    #[error("pattern `{0}` does not start with `aqaa` or `dedd`")]
+    InvalidNumberPrefix(String),
+    #[error("invalid objective `{0}`")]
+    InvalidObjective(String),
+    #[error("invalid ranking `{0}`")]
+    InvalidRanking(String),
+    #[error("value `{0}` is out of range")]
+    ValueOutOfRange(Ratio<u64>),
 }
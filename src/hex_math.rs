@@ -1,3 +1,5 @@
+//! Hex grid geometry: coordinates, directions, angles between directions, and the segments a pattern is made of.
+
 mod angle;
 mod coord;
 mod direction;
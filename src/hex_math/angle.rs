@@ -1,7 +1,9 @@
 use crate::errors::HexError;
 use num_rational::Ratio;
+use num_traits::{CheckedAdd, CheckedDiv, CheckedMul};
 use strum::EnumIter;
 
+/// A turn between two consecutive segments of a pattern, named by its character (eg. `Forward` is `w`).
 #[derive(Debug, Hash, Clone, Copy, PartialEq, Eq, EnumIter)]
 pub enum Angle {
     Forward = 0,
@@ -13,14 +15,30 @@ pub enum Angle {
 }
 
 impl Angle {
+    /// Applies the arithmetic this angle stands for in a number literal. `Back` can't be used in number literals, and
+    /// results that don't fit in a `u64` fail with [`HexError::ValueOutOfRange`].
     pub fn apply_to(&self, num: Ratio<u64>) -> Result<Ratio<u64>, HexError> {
+        let result = match self {
+            Angle::Forward => num.checked_add(&1.into()),
+            Angle::Left => num.checked_add(&5.into()),
+            Angle::Right => num.checked_add(&10.into()),
+            Angle::LeftBack => num.checked_mul(&2.into()),
+            Angle::RightBack => num.checked_div(&2.into()),
+            _ => return Err(HexError::InvalidAngle(*self)),
+        };
+        result.ok_or(HexError::ValueOutOfRange(num))
+    }
+
+    /// The value that this angle turns into `num`, ie. the inverse of [`Angle::apply_to`]. `None` if it would be
+    /// negative or the angle is `Back`.
+    pub fn unapply_to(&self, num: Ratio<u64>) -> Option<Ratio<u64>> {
         match self {
-            Angle::Forward => Ok(num + 1),
-            Angle::Left => Ok(num + 5),
-            Angle::Right => Ok(num + 10),
-            Angle::LeftBack => Ok(num * 2),
-            Angle::RightBack => Ok(num / 2),
-            _ => Err(HexError::InvalidAngle(*self)),
+            Angle::Forward => (num >= 1.into()).then(|| num - 1),
+            Angle::Left => (num >= 5.into()).then(|| num - 5),
+            Angle::Right => (num >= 10.into()).then(|| num - 10),
+            Angle::LeftBack => Some(num / 2),
+            Angle::RightBack => Some(num * 2),
+            Angle::Back => None,
         }
     }
 }
@@ -2,6 +2,7 @@ use std::ops::{Add, AddAssign, Neg, Sub};
 
 use super::{Angle, Direction};
 
+/// Axial hex coordinate. The third cube coordinate is derived as `s = -q - r`.
 #[derive(Debug, Hash, Clone, Copy, PartialEq, Eq)]
 pub struct Coord {
     q: i32,
@@ -29,6 +30,13 @@ impl Coord {
         -self.q - self.r
     }
 
+    /// Both coordinates packed into one integer, 16 bits each, for occupancy filters. Only unique while they fit in an
+    /// `i16`.
+    pub(crate) fn packed(&self) -> u32 {
+        (self.q as u16 as u32) << 16 | self.r as u16 as u32
+    }
+
+    /// Rotates clockwise around the origin.
     pub fn rotated(&self, angle: Angle) -> Self {
         let mut rotated = *self;
         for _ in 0..(angle as i32) {
@@ -1,7 +1,9 @@
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 
 use super::Angle;
+use crate::errors::HexError;
 
+/// One of the six directions a segment can point in. Parses from and displays as eg. `SOUTH_EAST`.
 #[derive(Debug, Hash, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
     NorthEast = 0,
@@ -17,6 +19,7 @@ impl Direction {
         matches!(*self, Self::NorthEast | Self::East | Self::SouthEast)
     }
 
+    /// The angle to turn by to get from `other` to `self`.
     pub fn angle_from(&self, other: Self) -> Angle {
         Angle::from(*self as i32 - other as i32)
     }
@@ -53,3 +56,19 @@ impl Display for Direction {
         write!(f, "{}", name)
     }
 }
+
+impl FromStr for Direction {
+    type Err = HexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "NORTH_EAST" => Ok(Direction::NorthEast),
+            "EAST" => Ok(Direction::East),
+            "SOUTH_EAST" => Ok(Direction::SouthEast),
+            "SOUTH_WEST" => Ok(Direction::SouthWest),
+            "WEST" => Ok(Direction::West),
+            "NORTH_WEST" => Ok(Direction::NorthWest),
+            _ => Err(HexError::InvalidDirection(s.to_string())),
+        }
+    }
+}
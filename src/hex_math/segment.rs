@@ -3,6 +3,9 @@ use std::hash::Hash;
 use super::{Angle, Coord, Direction};
 use crate::errors::HexError;
 
+/// A line between two adjacent points.
+///
+/// Segments compare and hash the same regardless of which way they were drawn.
 #[derive(Debug, Clone, Copy)]
 pub struct Segment {
     root: Coord,
@@ -30,15 +33,23 @@ impl Segment {
         Self::new(self.root.rotated(angle), self.direction.rotated(angle))
     }
 
+    /// The segment drawn after this one when turning by `angle`.
     pub fn next_segment(&self, angle: Angle) -> Self {
         Self::new(self.end(), self.direction.rotated(angle))
     }
 
+    /// The canonical root and direction packed into one integer, for occupancy filters. Only unique while the root's
+    /// coordinates fit in an `i16`.
+    pub(crate) fn packed(&self) -> u64 {
+        (self.canonical_root().packed() as u64) << 2 | self.canonical_direction() as u64
+    }
+
     fn is_canonical(&self) -> bool {
         self.direction.is_east()
     }
 
-    fn canonical_root(&self) -> Coord {
+    /// The end of the segment that it's stored and compared from, so that segments drawn either way match.
+    pub fn canonical_root(&self) -> Coord {
         if self.is_canonical() {
             self.root
         } else {
@@ -46,7 +57,8 @@ impl Segment {
         }
     }
 
-    fn canonical_direction(&self) -> Direction {
+    /// Always [`Direction::NorthEast`], [`Direction::East`] or [`Direction::SouthEast`].
+    pub fn canonical_direction(&self) -> Direction {
         if self.is_canonical() {
             self.direction
         } else {
@@ -69,6 +81,7 @@ impl PartialEq for Segment {
 }
 impl Eq for Segment {}
 
+/// Traces a pattern from the origin, returning every segment including the first one.
 pub fn get_pattern_segments(direction: Direction, pattern: &str) -> Result<Vec<Segment>, HexError> {
     let mut cursor = Coord::origin();
     let mut compass = direction;
@@ -84,3 +97,22 @@ pub fn get_pattern_segments(direction: Direction, pattern: &str) -> Result<Vec<S
 
     Ok(segments)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_regardless_of_drawing_direction() {
+        let segment = Segment::new(Coord::new(1, -2), Direction::West);
+        assert_eq!(segment, Segment::new(segment.end(), Direction::East));
+        assert_ne!(segment, Segment::new(segment.end(), Direction::West));
+    }
+
+    #[test]
+    fn compares_exactly_far_from_the_origin() {
+        let segment = Segment::new(Coord::origin(), Direction::East);
+        assert_ne!(segment, Segment::new(Coord::new(1 << 16, 0), Direction::East));
+        assert_ne!(segment, Segment::new(Coord::new(0, -(1 << 16)), Direction::East));
+    }
+}
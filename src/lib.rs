@@ -1,52 +1,101 @@
-mod errors;
-mod hex_math;
-mod numgen;
-mod traits;
+//! Generates and decodes Hex Casting number literal patterns.
+//!
+//! The [`generate_number_pattern_beam`] and [`generate_number_pattern_astar`] functions cover the common cases. The
+//! building blocks they use ([`numgen::Path`], the generators, and the [`hex_math`] types) are public for tools that
+//! need more control.
+//!
+//! Python bindings are available behind the `python` feature.
+
+pub mod errors;
+pub mod hex_math;
+pub mod numgen;
+#[cfg(feature = "python")]
+mod python;
+pub mod traits;
 mod utils;
 
+use std::fmt::Display;
+
 use num_rational::Ratio;
-use numgen::{AStarPathGenerator, BeamPathGenerator};
+
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
 
-pub use hex_math::Direction;
-pub use numgen::Bounds;
+pub use errors::HexError;
+pub use hex_math::{Angle, Coord, Direction, Segment};
+pub use numgen::{
+    decode_number_pattern, validate_pattern, AStarPathGenerator, Algorithm, BeamPathGenerator,
+    BidirectionalPathGenerator, Bounds, BoundsAttempt, BoundsMetric, Collect, ExhaustivePathGenerator, Feasibility,
+    FeasibilityCheck, FeasibilityReport, GeneratorConfig, Improvement, Improvements, Metric, MinimumBounds,
+    MinimumBoundsSearch, Objective, Optimality, Path, PathGenerator, PatternReport, PruningRule, RankKey, Ranking,
+    SearchLimits, SearchOutcome, SmaStarPathGenerator, Weighted,
+};
+pub use utils::NonZeroSign;
 
-#[derive(FromPyObject)]
-pub enum PyRatio {
-    #[pyo3(annotation = "int")]
-    Int(i64),
-    #[pyo3(annotation = "tuple[int, int]")]
-    Tuple(i64, i64),
-}
-
-impl From<PyRatio> for Ratio<i64> {
-    fn from(value: PyRatio) -> Self {
-        match value {
-            PyRatio::Int(n) => n.into(),
-            PyRatio::Tuple(numer, denom) => Ratio::new(numer, denom),
-        }
-    }
-}
-
-#[pyclass]
+/// A generated pattern, in the format used by Hex Casting.
+#[cfg_attr(feature = "python", pyclass)]
 pub struct GeneratedNumber {
-    #[pyo3(get)]
+    /// Starting direction, eg. `SOUTH_EAST`
     pub direction: String,
-    #[pyo3(get)]
+    /// Angle string, eg. `aqaaeaqaa`
     pub pattern: String,
-    #[pyo3(get)]
+    pub quasi_area: u32,
     pub largest_dimension: u32,
-    #[pyo3(get)]
     pub num_points: usize,
+    /// Whether the search hit one of its [`SearchLimits`] before finishing, so a smaller pattern may exist
+    pub stopped_early: bool,
+    pub optimality: Optimality,
+    /// See [`SearchOutcome::lower_bound`]
+    pub lower_bound: Option<u64>,
+    /// See [`SearchOutcome::within_factor`]
+    pub within_factor: Option<f64>,
+    /// See [`SearchOutcome::seed`]
+    pub seed: Option<u64>,
+}
+
+impl Display for GeneratedNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.direction, self.pattern)
+    }
 }
 
-#[pymethods]
-impl GeneratedNumber {
-    fn __str__(&self) -> String {
-        format!("{} {}", self.direction, self.pattern)
+impl From<Path> for GeneratedNumber {
+    fn from(path: Path) -> Self {
+        Self {
+            direction: path.starting_direction().to_string(),
+            pattern: path.pattern(),
+            quasi_area: path.bounds().quasi_area(),
+            largest_dimension: path.bounds().largest_dimension(),
+            num_points: path.num_points(),
+            stopped_early: false,
+            optimality: Optimality::Heuristic,
+            lower_bound: None,
+            within_factor: None,
+            seed: None,
+        }
     }
 }
 
+/// Generates a pattern using any of the available algorithms.
+pub fn generate_number_pattern(config: GeneratorConfig, algorithm: Algorithm) -> Option<GeneratedNumber> {
+    generate_number_patterns(config, algorithm).into_iter().next()
+}
+
+/// Generates every pattern kept according to [`GeneratorConfig::collect`], best first.
+pub fn generate_number_patterns(config: GeneratorConfig, algorithm: Algorithm) -> Vec<GeneratedNumber> {
+    let objective = config.objective.clone();
+    let outcome = algorithm.generator(config).run();
+    let (stopped_early, optimality, lower_bound, seed) =
+        (outcome.stopped_early(), outcome.optimality, outcome.lower_bound, outcome.seed);
+    let within_factor = outcome.within_factor(&*objective);
+    outcome
+        .solutions
+        .into_iter()
+        .map(|path| GeneratedNumber { stopped_early, optimality, lower_bound, within_factor, seed, ..path.into() })
+        .collect()
+}
+
+/// Generates a pattern for `target` using beam search, keeping `carryover` paths per step.
 pub fn generate_number_pattern_beam(
     target: Ratio<i64>,
     bounds: Bounds,
@@ -54,64 +103,33 @@ pub fn generate_number_pattern_beam(
     trim_larger: bool,
     allow_fractions: bool,
 ) -> Option<GeneratedNumber> {
-    let path = BeamPathGenerator::new(target, bounds, carryover, trim_larger, allow_fractions).run()?;
-    Some(GeneratedNumber {
-        direction: path.starting_direction().to_string(),
-        pattern: path.pattern(),
-        largest_dimension: path.bounds().largest_dimension(),
-        num_points: path.num_points(),
-    })
+    let config = GeneratorConfig {
+        bounds: Some(bounds),
+        carryover,
+        trim_larger,
+        allow_fractions,
+        ..GeneratorConfig::new(target)
+    };
+    generate_number_pattern(config, Algorithm::Beam)
 }
 
+/// Generates a pattern for `target` using unbounded A* search.
 pub fn generate_number_pattern_astar(
     target: Ratio<i64>,
     trim_larger: bool,
     allow_fractions: bool,
 ) -> Option<GeneratedNumber> {
-    let path = AStarPathGenerator::new(target, trim_larger, allow_fractions).run()?;
-    Some(GeneratedNumber {
-        direction: path.starting_direction().to_string(),
-        pattern: path.pattern(),
-        largest_dimension: path.bounds().largest_dimension(),
-        num_points: path.num_points(),
-    })
-}
-
-#[pyfunction]
-#[pyo3(name = "generate_number_pattern_beam")]
-#[allow(clippy::too_many_arguments)]
-fn generate_number_pattern_beam_py(
-    target: PyRatio,
-    q_size: Option<u32>,
-    r_size: Option<u32>,
-    s_size: Option<u32>,
-    carryover: Option<usize>,
-    trim_larger: Option<bool>,
-    allow_fractions: Option<bool>,
-) -> Option<GeneratedNumber> {
-    generate_number_pattern_beam(
-        target.into(),
-        Bounds::new(q_size.unwrap_or(8), r_size.unwrap_or(8), s_size.unwrap_or(8)),
-        carryover.unwrap_or(25),
-        trim_larger.unwrap_or(true),
-        allow_fractions.unwrap_or(false),
-    )
+    let config = GeneratorConfig { bounds: None, trim_larger, allow_fractions, ..GeneratorConfig::new(target) };
+    generate_number_pattern(config, Algorithm::AStar)
 }
 
-#[pyfunction]
-#[pyo3(name = "generate_number_pattern_astar")]
-fn generate_number_pattern_astar_py(
-    target: PyRatio,
-    trim_larger: Option<bool>,
-    allow_fractions: Option<bool>,
+/// Generates a pattern for `target` using A* search limited to `bounds`.
+pub fn generate_number_pattern_astar_bounded(
+    target: Ratio<i64>,
+    bounds: Bounds,
+    trim_larger: bool,
+    allow_fractions: bool,
 ) -> Option<GeneratedNumber> {
-    generate_number_pattern_astar(target.into(), trim_larger.unwrap_or(true), allow_fractions.unwrap_or(false))
-}
-
-#[pymodule]
-fn hexnumgen(_py: Python, m: &PyModule) -> PyResult<()> {
-    m.add_function(wrap_pyfunction!(generate_number_pattern_beam_py, m)?)?;
-    m.add_function(wrap_pyfunction!(generate_number_pattern_astar_py, m)?)?;
-    m.add_class::<GeneratedNumber>()?;
-    Ok(())
+    let config = GeneratorConfig { bounds: Some(bounds), trim_larger, allow_fractions, ..GeneratorConfig::new(target) };
+    generate_number_pattern(config, Algorithm::AStar)
 }
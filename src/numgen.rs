@@ -1,13 +1,52 @@
+//! Number pattern generation, plus decoding and validation of existing patterns.
+
 mod astar_generator;
 mod beam_generator;
+mod bidirectional_generator;
 mod bounds;
+mod bounds_search;
+mod bucket_queue;
+mod config;
+mod decoder;
+mod distance;
+mod exhaustive_generator;
+mod feasibility;
+mod generator;
+mod improvements;
+mod limits;
 mod minmax;
+mod objective;
+mod occupancy;
+mod parallel;
 mod path;
-mod queued_path;
+mod ranking;
+mod sma_generator;
+mod solutions;
+mod transpositions;
+mod validator;
 
 pub use astar_generator::AStarPathGenerator;
 pub use beam_generator::BeamPathGenerator;
+pub use bidirectional_generator::BidirectionalPathGenerator;
 pub use bounds::Bounds;
-pub use minmax::MinMax;
+pub use bounds_search::{BoundsAttempt, BoundsMetric, MinimumBounds, MinimumBoundsSearch};
+pub(crate) use bucket_queue::BucketQueue;
+pub use config::GeneratorConfig;
+pub use decoder::decode_number_pattern;
+pub(crate) use distance::DistanceTable;
+pub use exhaustive_generator::ExhaustivePathGenerator;
+pub use feasibility::{Feasibility, FeasibilityCheck, FeasibilityReport, PruningRule};
+pub use generator::{Algorithm, Optimality, PathGenerator, SearchOutcome};
+pub use improvements::{Improvement, Improvements};
+pub(crate) use limits::Budget;
+pub use limits::{CancellationToken, SearchLimits, StopReason};
+pub(crate) use minmax::MinMax;
+pub use objective::{Metric, Objective, Weighted};
+pub(crate) use occupancy::{Lookup, Occupancy};
 pub use path::Path;
-pub use queued_path::QueuedPath;
+pub use ranking::{RankKey, Ranking};
+pub use sma_generator::SmaStarPathGenerator;
+pub use solutions::Collect;
+pub(crate) use solutions::Solutions;
+pub(crate) use transpositions::TranspositionTable;
+pub use validator::{validate_pattern, PatternReport};
@@ -4,84 +4,77 @@ use strum::IntoEnumIterator;
 
 use crate::{hex_math::Angle, traits::UnsignedAbsRatio, utils::NonZeroSign};
 
-use super::{Path, QueuedPath};
-use std::collections::BinaryHeap;
-
+use super::{
+    parallel, BucketQueue, Budget, DistanceTable, GeneratorConfig, Improvement, Optimality, Path, PathGenerator,
+    SearchOutcome, Solutions, TranspositionTable,
+};
+use std::iter;
+
+/// Best-first search that keeps going until no remaining path could beat the smallest solution found.
+///
+/// Paths are expanded in order of their length plus the fewest angles that could still reach the target, so with
+/// [`Metric::Length`](super::Metric::Length) as the objective the first solution found is already the shortest.
+///
+/// With more than one thread, the best [`AStarPathGenerator::BATCH_SIZE`] paths are taken off the frontier at once and
+/// expanded in parallel, then their children are added back in order, so the result is the same for any thread count
+/// above one. With one thread paths are expanded one at a time, which can find a different pattern of the same cost.
 pub struct AStarPathGenerator {
     target: Ratio<u64>,
-    trim_larger: bool,
-    allow_fractions: bool,
-    smallest: Option<Path>,
-    frontier: BinaryHeap<QueuedPath>,
+    config: GeneratorConfig,
+    distances: DistanceTable,
+    solutions: Solutions,
+    transpositions: TranspositionTable,
+    frontier: BucketQueue<Path>,
 }
 
 impl AStarPathGenerator {
-    pub fn new(target: Ratio<i64>, trim_larger: bool, allow_fractions: bool) -> Self {
+    /// Number of paths expanded per step when running on more than one thread.
+    pub const BATCH_SIZE: usize = 256;
+
+    pub fn new(config: GeneratorConfig) -> Self {
         let mut gen = Self {
-            target: target.unsigned_abs(),
-            trim_larger,
-            allow_fractions,
-            smallest: None,
-            frontier: BinaryHeap::new(),
+            target: config.target.unsigned_abs(),
+            distances: DistanceTable::new(&config),
+            solutions: Solutions::new(&config),
+            transpositions: TranspositionTable::new(&config),
+            frontier: BucketQueue::new(),
+            config,
         };
-        gen.push_path(Path::zero(NonZeroSign::from(target)));
+        gen.push_path(Path::zero_within(NonZeroSign::from(gen.config.target), gen.config.bounds));
         gen
     }
 
-    pub fn run(mut self) -> Option<Path> {
-        if self.target.is_zero() {
-            return self.frontier.pop().map(Into::into);
-        }
-
-        while !self.frontier.is_empty() {
-            // i really wish if-let chains were stable
-            if self.update_frontier() {
-                if let Some(smallest_in_frontier) = self
-                    .frontier
-                    .iter()
-                    .map(|qp| &qp.path)
-                    .filter(|path| path.value() == self.target)
-                    .min_by_key(|path| path.bounds().quasi_area())
-                {
-                    if smallest_in_frontier.should_replace(&self.smallest) {
-                        let smallest = smallest_in_frontier.clone();
-
-                        // i really wish BinaryHeap retain was stable
-                        self.frontier = BinaryHeap::from_iter(
-                            self.frontier.into_iter().filter(|qp| qp.path.bounds().is_better_than(smallest.bounds())),
-                        );
-
-                        self.smallest = Some(smallest);
-                    }
-                }
+    /// Expands the next batch of paths, returning how many were expanded and any new solutions that were kept
+    fn update_frontier(&mut self) -> (usize, Vec<Path>) {
+        let batch_size = if self.config.threads > 1 { Self::BATCH_SIZE } else { 1 };
+        let (frontier, solutions, distances) = (&mut self.frontier, &self.solutions, &self.distances);
+        // paths are only dropped once popped, rather than every time a better solution turns up
+        let batch: Vec<_> = iter::from_fn(|| frontier.pop())
+            .filter(|path| solutions.could_improve(path, distances.steps_left(path.value())))
+            .take(batch_size)
+            .collect();
+        let children = parallel::map(&batch, self.config.threads, |path| self.next_paths(path));
+        let mut kept = Vec::new();
+
+        for new_path in children.into_iter().flatten() {
+            if !self.transpositions.insert(&new_path) {
+                continue;
             }
-        }
-
-        self.smallest
-    }
-
-    /// Returns true if there are valid solutions in the new frontier
-    fn update_frontier(&mut self) -> bool {
-        let path = self.frontier.pop().unwrap().path;
-        let mut has_valid_solutions = false;
-
-        for new_path in self.next_paths(path) {
-            if new_path.value() == self.target {
-                has_valid_solutions = true;
+            if new_path.value() == self.target && self.solutions.offer(&new_path) {
+                kept.push(new_path.clone());
             }
             self.push_path(new_path);
         }
 
-        has_valid_solutions
+        (batch.len(), kept)
     }
 
-    fn next_paths(&self, path: Path) -> Vec<Path> {
+    fn next_paths(&self, path: &Path) -> Vec<Path> {
         Angle::iter()
             .filter_map(|angle| {
                 if let Ok(new_path) = path.with_angle(angle) {
-                    if (!self.trim_larger || new_path.value() <= self.target)
-                        && (self.allow_fractions || new_path.value().is_integer())
-                        && new_path.should_replace(&self.smallest)
+                    if self.config.allows(&new_path)
+                        && self.solutions.could_improve(&new_path, self.distances.steps_left(new_path.value()))
                     {
                         return Some(new_path);
                     }
@@ -91,38 +84,61 @@ impl AStarPathGenerator {
             .collect()
     }
 
-    fn heuristic(&mut self, path: &Path) -> usize {
-        let mut val = path.value();
-        let mut target = self.target;
-        let mut heuristic = path.len();
+    /// Never overestimates the length of the shortest finished path this could be extended into, unless the heuristic is
+    /// weighted by [`GeneratorConfig::epsilon`].
+    fn heuristic(&self, path: &Path) -> usize {
+        let steps_left = self.distances.steps_left(path.value());
+        path.len() + (steps_left as f64 * self.config.epsilon.max(1.0)).ceil() as usize
+    }
 
-        if val.is_zero() {
-            heuristic += 1;
+    fn push_path(&mut self, path: Path) {
+        let priority = self.heuristic(&path);
+        self.frontier.push(priority, path);
+    }
+}
 
-            if target > 10.into() {
-                val += 10;
-            } else if target > 5.into() {
-                val += 5;
-            } else {
-                val += 1;
-            }
-        }
+impl PathGenerator for AStarPathGenerator {
+    fn run_with(&mut self, on_improvement: &mut dyn FnMut(&Improvement)) -> SearchOutcome {
+        let mut budget = Budget::start(self.config.limits.clone());
 
-        while val > target {
-            val /= 2;
-            heuristic += 1;
+        if self.target.is_zero() {
+            let path = self.frontier.pop().unwrap();
+            return SearchOutcome::zero(path, &self.config, &budget, on_improvement);
         }
 
-        while target / 2 > val {
-            target /= 2;
-            heuristic += 1;
-        }
+        let mut stop_reason = None;
 
-        heuristic
-    }
+        while !self.frontier.is_empty() {
+            stop_reason = budget.exhausted(self.frontier.len());
+            if stop_reason.is_some() {
+                break;
+            }
+            let (expanded, kept) = self.update_frontier();
+            budget.expand(expanded);
 
-    fn push_path(&mut self, path: Path) {
-        let priority = self.heuristic(&path);
-        self.frontier.push(QueuedPath { path, priority });
+            for path in &kept {
+                on_improvement(&Improvement::new(path, budget.elapsed()));
+            }
+        }
+
+        let unexplored = self
+            .frontier
+            .iter()
+            .map(|path| (path, self.distances.steps_left(path.value())))
+            .filter(|&(path, steps_left)| self.solutions.could_improve(path, steps_left))
+            .map(|(path, steps_left)| self.config.objective.lower_bound(path, steps_left))
+            .min();
+
+        SearchOutcome {
+            solutions: self.solutions.paths().to_vec(),
+            optimality: if stop_reason.is_none() {
+                Optimality::exhaustive(&self.config)
+            } else {
+                Optimality::Heuristic
+            },
+            lower_bound: self.solutions.lower_bound(unexplored),
+            seed: None,
+            stop_reason,
+        }
     }
 }
@@ -4,78 +4,126 @@ use crate::{
     hex_math::Angle,
     traits::{AbsDiffRatio, UnsignedAbsRatio},
 };
-use itertools::Itertools;
 use num_rational::Ratio;
 use num_traits::Zero;
+use rand::{
+    rngs::StdRng,
+    seq::{index, SliceRandom},
+    SeedableRng,
+};
 use strum::IntoEnumIterator;
 
-use super::{Bounds, Path};
+use super::{
+    parallel, Budget, GeneratorConfig, Improvement, Optimality, Path, PathGenerator, RankKey, SearchOutcome, Solutions,
+    TranspositionTable,
+};
+
+/// `|ln(1 + value) - ln(1 + target)|`, as bits that sort the same way as the distance itself. The 1 keeps zero finite.
+fn log_distance(value: Ratio<u64>, target: Ratio<u64>) -> u64 {
+    let ln = |x: Ratio<u64>| (*x.numer() as f64 / *x.denom() as f64).ln_1p();
+    (ln(value) - ln(target)).abs().to_bits()
+}
 
+/// Breadth-first search that only keeps the best paths by each key of [`GeneratorConfig::ranking`] after every step.
+/// Transpositions are only checked against the paths in the beam.
+///
+/// With [`GeneratorConfig::stochastic`] set, the paths kept are sampled instead, favouring better ranks. Each of
+/// [`GeneratorConfig::restarts`] runs the whole search again from scratch with the next seed, unless nothing is random,
+/// in which case every run would be the same.
 pub struct BeamPathGenerator {
     target: Ratio<u64>,
-    bounds: Bounds,
-    carryover: usize,
-    trim_larger: bool,
-    allow_fractions: bool,
-    smallest: Option<Path>,
+    config: GeneratorConfig,
+    /// Solutions kept across all runs
+    solutions: Solutions,
+    /// Solutions kept by the current run, which prune it independently of other runs so that its seed reproduces it
+    run_solutions: Solutions,
+    transpositions: TranspositionTable,
     paths: Vec<Path>,
+    /// Lowest objective lower bound among paths that were trimmed from the beam, if any were
+    dropped_bound: Option<u64>,
+    /// Sampling and order for [`RankKey::Random`]
+    rng: StdRng,
 }
 
 impl BeamPathGenerator {
-    pub fn new(target: Ratio<i64>, bounds: Bounds, carryover: usize, trim_larger: bool, allow_fractions: bool) -> Self {
+    pub fn new(config: GeneratorConfig) -> Self {
         Self {
-            target: target.unsigned_abs(),
-            bounds,
-            carryover,
-            trim_larger,
-            allow_fractions,
-            smallest: None,
-            paths: vec![Path::zero(target.into())],
+            target: config.target.unsigned_abs(),
+            solutions: Solutions::new(&config),
+            run_solutions: Solutions::new(&config),
+            transpositions: TranspositionTable::new(&config),
+            paths: vec![Path::zero_within(config.target.into(), config.bounds)],
+            dropped_bound: None,
+            rng: StdRng::seed_from_u64(config.seed),
+            config,
         }
     }
 
-    pub fn run(mut self) -> Option<Path> {
-        if self.target.is_zero() {
-            return Some(self.paths[0].clone());
-        }
-        while !self.paths.is_empty() {
-            self.expand();
-            self.trim_to_best();
-            self.update_smallest();
+    /// Resets everything but the overall solutions for a run with the given seed.
+    fn restart(&mut self, seed: u64) {
+        self.run_solutions = Solutions::new(&self.config);
+        self.transpositions = TranspositionTable::new(&self.config);
+        self.paths = vec![Path::zero_within(self.config.target.into(), self.config.bounds)];
+        self.dropped_bound = None;
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// Number of extra runs, which are skipped if they'd only repeat the first one.
+    fn restarts(&self) -> usize {
+        let random = self.config.stochastic || self.config.ranking.0.iter().any(|&(key, _)| key == RankKey::Random);
+        if random {
+            self.config.restarts
+        } else {
+            0
         }
-        self.smallest
     }
 
     fn expand(&mut self) {
-        self.paths = self
-            .paths
-            .iter()
-            .cartesian_product(Angle::iter())
-            .filter_map(|(path, angle)| {
-                if let Ok(new_path) = path.with_angle(angle) {
-                    if (!self.trim_larger || new_path.value() <= self.target)
-                        && (self.allow_fractions || new_path.value().is_integer())
-                        && new_path.bounds().fits_in(self.bounds)
-                    {
-                        return Some(new_path);
-                    }
-                }
-                None
-            })
-            .collect();
+        let (config, solutions) = (&self.config, &self.run_solutions);
+        let children = parallel::map(&self.paths, config.threads, |path| {
+            Angle::iter()
+                .filter_map(|angle| path.with_angle(angle).ok())
+                .filter(|new_path| config.allows(new_path) && solutions.could_improve(new_path, 0))
+                .collect::<Vec<_>>()
+        });
+        self.paths = children.into_iter().flatten().collect();
+
+        let transpositions = &mut self.transpositions;
+        self.paths.retain(|path| transpositions.insert(path));
     }
 
-    fn filter_by_key<F, K>(&mut self, paths: &mut Vec<Path>, f: F)
+    fn filter_by_key<F, K>(&mut self, paths: &mut Vec<Path>, quota: usize, f: F)
     where
-        F: FnMut(&Path) -> K,
+        F: Fn(&Path) -> K + Sync,
         K: Ord,
     {
-        paths.sort_by_key(f);
+        *paths = parallel::sort_by_key(mem::take(paths), self.config.threads, f);
+        self.keep_first(paths, quota);
+    }
+
+    /// Moves the first `quota` paths into the beam, or samples `quota` of them favouring the first ones if the search is
+    /// stochastic.
+    fn keep_first(&mut self, paths: &mut Vec<Path>, quota: usize) {
+        if self.config.stochastic && quota < paths.len() {
+            let mut sampled = index::sample_weighted(&mut self.rng, paths.len(), |i| 1.0 / (i + 1) as f64, quota)
+                .expect("rank weights are positive")
+                .into_vec();
+            sampled.sort_unstable();
 
-        if self.carryover > paths.len() {
+            let mut sampled = sampled.into_iter().peekable();
+            let mut rest = Vec::with_capacity(paths.len() - quota);
+            for (i, path) in mem::take(paths).into_iter().enumerate() {
+                if sampled.next_if_eq(&i).is_some() {
+                    self.paths.push(path);
+                } else {
+                    rest.push(path);
+                }
+            }
+            *paths = rest;
+        } else if quota > paths.len() {
             self.paths.append(paths);
         } else {
-            let rest = paths.split_off(self.carryover);
+            let rest = paths.split_off(quota);
             self.paths.append(paths);
             paths.extend(rest);
         }
@@ -87,21 +135,156 @@ impl BeamPathGenerator {
 
         let target = self.target;
 
-        self.filter_by_key(&mut rest, |path| path.len()); // shortest
-        self.filter_by_key(&mut rest, |path| path.value().abs_diff(target)); // closest to target
-        self.filter_by_key(&mut rest, |path| path.num_points()); // fewest points
+        for (key, quota) in self.config.ranking.0.clone() {
+            let quota = quota.unwrap_or(self.config.carryover);
+            match key {
+                RankKey::Length => self.filter_by_key(&mut rest, quota, |path| path.len()),
+                RankKey::Distance => self.filter_by_key(&mut rest, quota, |path| path.value().abs_diff(target)),
+                RankKey::LogDistance => self.filter_by_key(&mut rest, quota, |path| log_distance(path.value(), target)),
+                RankKey::NumPoints => self.filter_by_key(&mut rest, quota, |path| path.num_points()),
+                RankKey::QuasiArea => self.filter_by_key(&mut rest, quota, |path| path.bounds().quasi_area()),
+                RankKey::LargestDimension => {
+                    self.filter_by_key(&mut rest, quota, |path| path.bounds().largest_dimension())
+                }
+                RankKey::Random => {
+                    rest.shuffle(&mut self.rng);
+                    self.keep_first(&mut rest, quota);
+                }
+            }
+        }
+
+        self.dropped_bound = self.dropped_bound.into_iter().chain(self.bound_of(&rest)).min();
+        // paths that were dropped won't be expanded, so they only need to prune the paths they'd also be compared to
+        self.transpositions.keep_only(&self.paths);
+    }
+
+    fn bound_of(&self, paths: &[Path]) -> Option<u64> {
+        paths.iter().map(|path| self.config.objective.lower_bound(path, 0)).min()
     }
 
-    fn update_smallest(&mut self) {
+    /// Moves finished paths out of the beam, returning any new solutions that were kept
+    fn update_solutions(&mut self) -> Vec<Path> {
         let mut rest = Vec::new();
         mem::swap(&mut self.paths, &mut rest);
+        let mut kept = Vec::new();
 
         for path in rest {
             if path.value() != self.target {
                 self.paths.push(path);
-            } else if path.should_replace(&self.smallest) {
-                self.smallest = Some(path);
+            } else if self.run_solutions.offer(&path) {
+                kept.push(path);
+            }
+        }
+
+        kept
+    }
+}
+
+impl PathGenerator for BeamPathGenerator {
+    fn run_with(&mut self, on_improvement: &mut dyn FnMut(&Improvement)) -> SearchOutcome {
+        let mut budget = Budget::start(self.config.limits.clone());
+
+        if self.target.is_zero() {
+            let path = self.paths[0].clone();
+            return SearchOutcome::zero(path, &self.config, &budget, on_improvement);
+        }
+
+        let mut stop_reason = None;
+        let mut exhaustive = false;
+        let mut lower_bound = None;
+        let mut best_seed = None;
+
+        for restart in 0..=self.restarts() {
+            let seed = self.config.seed.wrapping_add(restart as u64);
+            self.restart(seed);
+
+            while !self.paths.is_empty() {
+                budget.expand(self.paths.len());
+                self.expand();
+
+                stop_reason = budget.exhausted(self.paths.len());
+
+                self.trim_to_best();
+                for path in self.update_solutions() {
+                    if self.solutions.offer(&path) {
+                        if self.solutions.best().is_some_and(|best| best.is_same(&path)) {
+                            best_seed = Some(seed);
+                        }
+                        on_improvement(&Improvement::new(&path, budget.elapsed()));
+                    }
+                }
+
+                if stop_reason.is_some() {
+                    break;
+                }
+            }
+
+            // every run's bound holds, so the best one can be used
+            exhaustive = stop_reason.is_none() && self.dropped_bound.is_none();
+            let unexplored = self.dropped_bound.into_iter().chain(self.bound_of(&self.paths)).min();
+            let run_bound = self.run_solutions.lower_bound(unexplored);
+            lower_bound = if exhaustive { run_bound } else { lower_bound.max(run_bound) };
+
+            if exhaustive || stop_reason.is_some() {
+                break;
             }
         }
+
+        SearchOutcome {
+            solutions: self.solutions.paths().to_vec(),
+            optimality: if exhaustive { Optimality::exhaustive(&self.config) } else { Optimality::Heuristic },
+            lower_bound,
+            seed: best_seed,
+            stop_reason,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex_math::Direction;
+
+    /// The patterns found, and the seed of the run that found the best one.
+    fn run(config: &GeneratorConfig) -> (Vec<(Direction, String)>, Option<u64>) {
+        let outcome = BeamPathGenerator::new(config.clone()).run();
+        let patterns = outcome.solutions.iter().map(|path| (path.starting_direction(), path.pattern())).collect();
+        (patterns, outcome.seed)
+    }
+
+    fn stochastic_config(target: i64, threads: usize) -> GeneratorConfig {
+        GeneratorConfig {
+            stochastic: true,
+            restarts: 3,
+            seed: 42,
+            threads,
+            ranking: "length,distance,random=10".parse().unwrap(),
+            ..GeneratorConfig::new(target.into())
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_same_pattern() {
+        for target in [137, 1234, -500] {
+            let expected = run(&stochastic_config(target, 1));
+            assert!(!expected.0.is_empty(), "nothing found for {target}");
+
+            for threads in [1, 4] {
+                let config = stochastic_config(target, threads);
+                assert_eq!(run(&config), run(&config), "{target} on {threads} threads");
+                assert_eq!(run(&config), expected, "{target} on {threads} threads");
+            }
+        }
+    }
+
+    #[test]
+    fn best_seed_reproduces_pattern() {
+        for threads in [1, 4] {
+            let config = stochastic_config(1234, threads);
+            let (patterns, seed) = run(&config);
+
+            let config = GeneratorConfig { seed: seed.unwrap(), restarts: 0, ..config };
+            assert_eq!(run(&config).0, patterns, "on {threads} threads");
+        }
     }
 }
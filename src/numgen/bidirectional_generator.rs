@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+
+use num_rational::Ratio;
+use num_traits::Zero;
+use strum::IntoEnumIterator;
+
+use crate::{hex_math::Angle, traits::UnsignedAbsRatio, utils::NonZeroSign};
+
+use super::{
+    BucketQueue, Budget, DistanceTable, GeneratorConfig, Improvement, Optimality, Path, PathGenerator, SearchOutcome,
+    Solutions, TranspositionTable,
+};
+
+/// Best-first search forwards from the prefix that meets a search backwards from the target on intermediate values.
+///
+/// Every angle sequence of up to [`GeneratorConfig::backward_depth`] angles that ends at the target is found up front,
+/// ignoring geometry, so there are up to 5^depth of them. Each forward path then tries the sequences that start from its
+/// value, so the forward search only has to get within that many angles of the target instead of all the way there.
+///
+/// The backward search also tells the forward search how many angles each path still needs at least, which only beats
+/// [`AStarPathGenerator`](super::AStarPathGenerator) by much when A*'s own estimate is loose, ie. with
+/// [`GeneratorConfig::trim_larger`] off. Otherwise A* already knows the exact number of angles left for each integer,
+/// and both take about the same time: 0.2s for 1234 and 1.4s for 4321 with default settings, and 1.8s and 1.6s for 1234
+/// with fractions allowed. With `trim_larger` off, this took 0.3s instead of 0.5s for 1234, 2s instead of 18s for
+/// 2718 and 5s instead of 150s for 4321. A larger `backward_depth` helps bigger targets, eg. 9999 took 43s with a depth
+/// of 8 instead of 136s with 6.
+pub struct BidirectionalPathGenerator {
+    target: Ratio<u64>,
+    config: GeneratorConfig,
+    distances: DistanceTable,
+    /// Angle sequences that take each value to the target, shortest first
+    completions: HashMap<Ratio<u64>, Vec<Vec<Angle>>>,
+    solutions: Solutions,
+    transpositions: TranspositionTable,
+    frontier: BucketQueue<Path>,
+}
+
+impl BidirectionalPathGenerator {
+    pub fn new(config: GeneratorConfig) -> Self {
+        Self {
+            target: config.target.unsigned_abs(),
+            distances: DistanceTable::new(&config),
+            completions: Self::search_backwards(&config),
+            solutions: Solutions::new(&config),
+            transpositions: TranspositionTable::new(&config),
+            frontier: BucketQueue::new(),
+            config,
+        }
+    }
+
+    fn search_backwards(config: &GeneratorConfig) -> HashMap<Ratio<u64>, Vec<Vec<Angle>>> {
+        let target = config.target.unsigned_abs();
+        let mut completions = HashMap::from([(target, vec![Vec::new()])]);
+        let mut layer = vec![(target, Vec::new())];
+
+        for _ in 0..config.backward_depth {
+            let mut next_layer = Vec::new();
+
+            for (value, angles) in &layer {
+                for angle in Angle::iter() {
+                    let Some(previous) = angle.unapply_to(*value).filter(|&previous| config.allows_value(previous))
+                    else {
+                        continue;
+                    };
+
+                    let angles: Vec<_> = [angle].into_iter().chain(angles.iter().copied()).collect();
+                    completions.entry(previous).or_insert_with(Vec::new).push(angles.clone());
+                    next_layer.push((previous, angles));
+                }
+            }
+
+            layer = next_layer;
+        }
+
+        completions
+    }
+
+    /// Every frontier path needs at least `backward_depth` more angles, since shorter endings were already tried from
+    /// one of its ancestors, and one more than that unless the backward search found an ending of exactly that length.
+    fn steps_left(&self, path: &Path) -> usize {
+        let depth = self.config.backward_depth;
+        let ends_at_depth = self.completions.get(&path.value()).is_some_and(|completions| {
+            // completions are shortest first
+            completions.last().is_some_and(|angles| angles.len() == depth)
+        });
+        self.distances.steps_left(path.value()).max(if ends_at_depth { depth } else { depth + 1 })
+    }
+
+    fn could_improve(&self, path: &Path) -> bool {
+        self.solutions.could_improve(path, self.steps_left(path))
+    }
+
+    /// Tries to draw each backward sequence of `min_len` or more angles onto the end of `path`, returning any new
+    /// solutions that were kept.
+    fn complete(&mut self, path: &Path, min_len: usize) -> Vec<Path> {
+        let Some(completions) = self.completions.get(&path.value()) else {
+            return Vec::new();
+        };
+        let mut kept = Vec::new();
+
+        for angles in completions.iter().filter(|angles| angles.len() >= min_len) {
+            let mut new_path = path.clone();
+
+            for (i, &angle) in angles.iter().enumerate() {
+                match new_path.with_angle(angle) {
+                    Ok(next)
+                        if self.config.allows(&next) && self.solutions.could_improve(&next, angles.len() - i - 1) =>
+                    {
+                        new_path = next;
+                    }
+                    _ => break,
+                }
+            }
+
+            if new_path.len() == path.len() + angles.len() && self.solutions.offer(&new_path) {
+                kept.push(new_path);
+            }
+        }
+
+        kept
+    }
+
+    fn push_children(&mut self, path: &Path) {
+        for angle in Angle::iter() {
+            if let Ok(new_path) = path.with_angle(angle) {
+                if self.config.allows(&new_path)
+                    && self.could_improve(&new_path)
+                    && self.transpositions.insert(&new_path)
+                {
+                    let priority = new_path.len() + self.steps_left(&new_path);
+                    self.frontier.push(priority, new_path);
+                }
+            }
+        }
+    }
+}
+
+impl PathGenerator for BidirectionalPathGenerator {
+    fn run_with(&mut self, on_improvement: &mut dyn FnMut(&Improvement)) -> SearchOutcome {
+        let mut budget = Budget::start(self.config.limits.clone());
+        let root = Path::zero_within(NonZeroSign::from(self.config.target), self.config.bounds);
+
+        if self.target.is_zero() {
+            return SearchOutcome::zero(root, &self.config, &budget, on_improvement);
+        }
+
+        let mut kept = self.complete(&root, 0);
+        self.push_children(&root);
+
+        let stop_reason = loop {
+            for path in &kept {
+                on_improvement(&Improvement::new(path, budget.elapsed()));
+            }
+            kept.clear();
+
+            if let Some(reason) = budget.exhausted(self.frontier.len()) {
+                break Some(reason);
+            }
+            let Some(path) = self.frontier.pop() else {
+                break None;
+            };
+            // the frontier isn't filtered when solutions are found, so this may have been overtaken since it was queued
+            if !self.could_improve(&path) {
+                continue;
+            }
+            budget.expand(1);
+
+            // anything shorter was already tried from one of this path's ancestors
+            kept = self.complete(&path, self.config.backward_depth);
+            self.push_children(&path);
+        };
+
+        let unexplored = self
+            .frontier
+            .iter()
+            .filter(|path| self.could_improve(path))
+            .map(|path| self.config.objective.lower_bound(path, self.steps_left(path)))
+            .min();
+
+        SearchOutcome {
+            solutions: self.solutions.paths().to_vec(),
+            optimality: if stop_reason.is_none() {
+                Optimality::exhaustive(&self.config)
+            } else {
+                Optimality::Heuristic
+            },
+            lower_bound: self.solutions.lower_bound(unexplored),
+            seed: None,
+            stop_reason,
+        }
+    }
+}
@@ -1,3 +1,4 @@
+/// Size of a pattern along each of the three hex axes, counted in points.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Bounds {
     q: u32,
@@ -10,12 +11,21 @@ impl Bounds {
         Self { q, r, s }
     }
 
-    pub fn quasi_area(&self) -> u32 {
-        self.q * self.r * self.s
+    pub fn q(&self) -> u32 {
+        self.q
+    }
+
+    pub fn r(&self) -> u32 {
+        self.r
     }
 
-    pub fn is_better_than(&self, other: Self) -> bool {
-        self.quasi_area() < other.quasi_area()
+    pub fn s(&self) -> u32 {
+        self.s
+    }
+
+    /// Product of the three dimensions, used to compare how much space patterns take up.
+    pub fn quasi_area(&self) -> u32 {
+        self.q * self.r * self.s
     }
 
     pub fn fits_in(&self, other: Self) -> bool {
@@ -23,7 +33,7 @@ impl Bounds {
     }
 
     pub fn largest_dimension(&self) -> u32 {
-        *[self.q, self.r, self.r].iter().max().unwrap()
+        *[self.q, self.r, self.s].iter().max().unwrap()
     }
 }
 
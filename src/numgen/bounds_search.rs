@@ -0,0 +1,148 @@
+use std::time::Instant;
+
+use strum::{Display, EnumString};
+
+use super::{BeamPathGenerator, Bounds, GeneratorConfig, Optimality, Path, PathGenerator, StopReason};
+
+/// Which size of [`Bounds`] a [`MinimumBoundsSearch`] makes as small as possible. Parses from and displays as eg.
+/// `quasi-area`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString)]
+#[strum(serialize_all = "kebab-case")]
+pub enum BoundsMetric {
+    QuasiArea,
+    LargestDimension,
+}
+
+impl BoundsMetric {
+    pub fn of(self, bounds: Bounds) -> u32 {
+        match self {
+            BoundsMetric::QuasiArea => bounds.quasi_area(),
+            BoundsMetric::LargestDimension => bounds.largest_dimension(),
+        }
+    }
+
+    /// Every shape of bounds a pattern could have that measures less than `limit`, smallest first.
+    fn candidates_below(self, limit: u32) -> Vec<Bounds> {
+        let mut candidates: Vec<_> = match self {
+            BoundsMetric::QuasiArea => (1..limit)
+                .flat_map(|q| (1..=(limit - 1) / q).map(move |r| (q, r)))
+                .flat_map(|(q, r)| (1..=(limit - 1) / (q * r)).map(move |s| Bounds::new(q, r, s)))
+                // q + r + s is zero on a hex grid, so each axis can only span as far as the other two put together
+                .filter(|b| b.q() < b.r() + b.s() && b.r() < b.q() + b.s() && b.s() < b.q() + b.r())
+                .collect(),
+            BoundsMetric::LargestDimension => (1..limit).map(Bounds::from).collect(),
+        };
+        candidates.sort_by_key(|&b| (self.of(b), b.largest_dimension(), b.q(), b.r(), b.s()));
+        candidates
+    }
+}
+
+/// One beam search run by a [`MinimumBoundsSearch`].
+#[derive(Debug, Clone)]
+pub struct BoundsAttempt {
+    pub bounds: Bounds,
+    pub carryover: usize,
+    /// Bounds of the best pattern found, if any
+    pub found: Option<Bounds>,
+    /// Whether nothing was trimmed from the beam, so there's no better pattern in `bounds`
+    pub exhaustive: bool,
+}
+
+/// The result of [`MinimumBoundsSearch::run`].
+#[derive(Clone)]
+pub struct MinimumBounds {
+    /// The smallest path found, if any, and the attempt that found it
+    pub best: Option<(Path, BoundsAttempt)>,
+    /// Every beam search that was run, in order
+    pub attempts: Vec<BoundsAttempt>,
+    /// Why the search stopped before it was finished, if it did
+    pub stop_reason: Option<StopReason>,
+}
+
+/// Finds the smallest bounds that beam search can fit a target into, instead of guessing sizes by hand.
+///
+/// First a cube is widened until beam search finds a pattern in it. Then every smaller shape is tried, smallest first,
+/// doubling the carryover from [`GeneratorConfig::carryover`] up to `max_carryover` before giving up on it. Shapes that
+/// fit in bounds already proven to be empty are skipped. The config's time limit covers the whole search.
+pub struct MinimumBoundsSearch {
+    config: GeneratorConfig,
+    by: BoundsMetric,
+    max_carryover: usize,
+}
+
+impl MinimumBoundsSearch {
+    /// Largest cube tried while widening.
+    pub const MAX_SIZE: u32 = 64;
+
+    pub fn new(config: GeneratorConfig, by: BoundsMetric, max_carryover: usize) -> Self {
+        Self { config, by, max_carryover }
+    }
+
+    pub fn run(&self) -> MinimumBounds {
+        let start = Instant::now();
+        let mut outcome = MinimumBounds { best: None, attempts: Vec::new(), stop_reason: None };
+
+        for size in 2..=Self::MAX_SIZE {
+            self.attempt(Bounds::from(size), self.config.carryover, start, &mut outcome);
+            if outcome.best.is_some() || outcome.stop_reason.is_some() {
+                break;
+            }
+        }
+        let Some(upper) = outcome.best.as_ref().map(|(path, _)| self.by.of(path.bounds())) else {
+            return outcome;
+        };
+
+        for bounds in self.by.candidates_below(upper) {
+            let mut carryover = self.config.carryover;
+
+            loop {
+                let proven_empty =
+                    outcome.attempts.iter().any(|a| a.exhaustive && a.found.is_none() && bounds.fits_in(a.bounds));
+                let tried = outcome.attempts.iter().any(|a| a.bounds == bounds && a.carryover == carryover);
+                if proven_empty {
+                    break;
+                }
+
+                if !tried {
+                    let previous = outcome.best.as_ref().map(|(path, _)| self.by.of(path.bounds()));
+                    self.attempt(bounds, carryover, start, &mut outcome);
+                    // candidates are smallest first, so the first one that works is the smallest
+                    let improved = outcome.best.as_ref().map(|(path, _)| self.by.of(path.bounds())) != previous;
+                    if improved || outcome.stop_reason.is_some() {
+                        return outcome;
+                    }
+                }
+
+                if carryover >= self.max_carryover {
+                    break;
+                }
+                carryover = (carryover * 2).min(self.max_carryover);
+            }
+        }
+
+        outcome
+    }
+
+    fn attempt(&self, bounds: Bounds, carryover: usize, start: Instant, outcome: &mut MinimumBounds) {
+        let mut config = GeneratorConfig { bounds: Some(bounds), carryover, ..self.config.clone() };
+        config.limits.time = self.config.limits.time.map(|time| time.saturating_sub(start.elapsed()));
+
+        let result = BeamPathGenerator::new(config).run();
+        let attempt = BoundsAttempt {
+            bounds,
+            carryover,
+            found: result.best().map(|path| path.bounds()),
+            exhaustive: result.optimality != Optimality::Heuristic,
+        };
+
+        if let Some(path) = result.best() {
+            let better =
+                outcome.best.as_ref().is_none_or(|(best, _)| self.by.of(path.bounds()) < self.by.of(best.bounds()));
+            if better {
+                outcome.best = Some((path.clone(), attempt.clone()));
+            }
+        }
+        outcome.stop_reason = result.stop_reason;
+        outcome.attempts.push(attempt);
+    }
+}
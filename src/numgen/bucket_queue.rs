@@ -0,0 +1,49 @@
+/// Min priority queue for small integer priorities, with one stack of items per priority.
+///
+/// Pushing and popping are O(1) apart from skipping over empty buckets. Items that stop being useful aren't removed,
+/// so callers should check what they pop.
+pub(crate) struct BucketQueue<T> {
+    buckets: Vec<Vec<T>>,
+    /// Every bucket below this is empty
+    lowest: usize,
+    len: usize,
+}
+
+impl<T> BucketQueue<T> {
+    pub fn new() -> Self {
+        Self { buckets: Vec::new(), lowest: 0, len: 0 }
+    }
+
+    pub fn push(&mut self, priority: usize, item: T) {
+        if priority >= self.buckets.len() {
+            self.buckets.resize_with(priority + 1, Vec::new);
+        }
+        self.buckets[priority].push(item);
+        self.lowest = self.lowest.min(priority);
+        self.len += 1;
+    }
+
+    /// Removes an item with the lowest priority, preferring the one pushed most recently.
+    pub fn pop(&mut self) -> Option<T> {
+        while let Some(bucket) = self.buckets.get_mut(self.lowest) {
+            if let Some(item) = bucket.pop() {
+                self.len -= 1;
+                return Some(item);
+            }
+            self.lowest += 1;
+        }
+        None
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.buckets.iter().flatten()
+    }
+}
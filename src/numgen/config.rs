@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use num_rational::Ratio;
+
+use super::{Bounds, Collect, Metric, Objective, Path, Ranking, SearchLimits};
+use crate::traits::UnsignedAbsRatio;
+
+/// Settings shared by all generators.
+///
+/// Start from [`GeneratorConfig::new`] and override fields with struct update syntax:
+///
+/// ```
+/// # use hexnumgen::{numgen::GeneratorConfig, Bounds};
+/// let config = GeneratorConfig { bounds: Some(Bounds::new(6, 6, 7)), ..GeneratorConfig::new(100.into()) };
+/// ```
+#[derive(Clone)]
+pub struct GeneratorConfig {
+    pub target: Ratio<i64>,
+    /// Discard paths that don't fit in these bounds, which default to 8 in every direction for every algorithm. Beam
+    /// search only terminates if this is set, and the other algorithms are unbounded if it's `None`
+    pub bounds: Option<Bounds>,
+    /// Number of paths kept per ranking between steps, unless the ranking has its own quota (beam search only)
+    pub carryover: usize,
+    /// Keys beam search ranks paths by, defaults to shortest, then closest to the target, then fewest points
+    pub ranking: Ranking,
+    /// Sample the paths kept by each ranking key, favouring better ranks, instead of keeping the best ones (beam search
+    /// only)
+    pub stochastic: bool,
+    /// Number of extra runs, each seeded with the next seed, keeping the best result (beam search only, and only if
+    /// `stochastic` is set or the ranking uses [`RankKey::Random`](super::RankKey::Random))
+    pub restarts: usize,
+    /// Seed for beam search's random choices
+    pub seed: u64,
+    /// Longest path searched, in segments including the prefix (exhaustive search only)
+    pub max_length: Option<usize>,
+    /// Number of angles searched backwards from the target before meeting the forward search (bidirectional search
+    /// only)
+    pub backward_depth: usize,
+    /// Most paths kept in memory at once, queued or expanded, before the worst queued ones are forgotten (SMA* only)
+    pub max_nodes: usize,
+    /// Discard paths whose value is larger than the target
+    pub trim_larger: bool,
+    /// Allow fractional intermediate values
+    pub allow_fractions: bool,
+    /// Skip paths that can't beat the best one found by more than this factor, and scale the A* heuristic by it. The
+    /// best path found then costs at most this many times as much as the best possible one. 1 is exact
+    pub epsilon: f64,
+    /// Skip paths dominated by an earlier path with the same value and end segment (ignored when collecting the top
+    /// `k` patterns, or if [`Objective::supports_dominance`] is false)
+    pub transpositions: bool,
+    /// Number of threads used to expand and rank paths (beam search and A* only). Results are the same for any count
+    /// above one, and with one the search runs on the calling thread. A* may then find a different pattern of the same
+    /// cost, since it expands one path at a time instead of a batch
+    pub threads: usize,
+    pub limits: SearchLimits,
+    /// What counts as the smallest path, defaults to [`Metric::QuasiArea`]
+    pub objective: Arc<dyn Objective>,
+    pub collect: Collect,
+}
+
+impl GeneratorConfig {
+    /// The default settings, which are bounded to 8 in every direction. Set `bounds` to `None` for an unbounded search.
+    pub fn new(target: Ratio<i64>) -> Self {
+        Self {
+            target,
+            bounds: Some(Bounds::from(8)),
+            carryover: 25,
+            ranking: Ranking::default(),
+            stochastic: false,
+            restarts: 0,
+            seed: 0,
+            max_length: None,
+            backward_depth: 6,
+            max_nodes: 1_000_000,
+            trim_larger: true,
+            allow_fractions: false,
+            epsilon: 1.0,
+            transpositions: true,
+            threads: 1,
+            limits: SearchLimits::default(),
+            objective: Arc::new(Metric::QuasiArea),
+            collect: Collect::Best,
+        }
+    }
+
+    /// Whether a path may hold this value, according to `trim_larger` and `allow_fractions`.
+    pub(crate) fn allows_value(&self, value: Ratio<u64>) -> bool {
+        (!self.trim_larger || value <= self.target.unsigned_abs()) && (self.allow_fractions || value.is_integer())
+    }
+
+    /// Whether a partial path is allowed by [`GeneratorConfig::allows_value`] and fits in the bounds.
+    pub(crate) fn allows(&self, path: &Path) -> bool {
+        self.allows_value(path.value()) && self.bounds.is_none_or(|bounds| path.bounds().fits_in(bounds))
+    }
+}
@@ -0,0 +1,93 @@
+use itertools::Itertools;
+use num_rational::Ratio;
+
+use crate::{
+    errors::HexError,
+    hex_math::{get_pattern_segments, Angle, Direction},
+    utils::NonZeroSign,
+};
+
+const POSITIVE_PREFIX: [Angle; 4] = [Angle::LeftBack, Angle::Left, Angle::LeftBack, Angle::LeftBack];
+const NEGATIVE_PREFIX: [Angle; 4] = [Angle::RightBack, Angle::Right, Angle::RightBack, Angle::RightBack];
+
+/// Computes the number drawn by a pattern, eg. `SOUTH_EAST aqaaweaq`.
+pub fn decode_number_pattern(direction: Direction, pattern: &str) -> Result<Ratio<i64>, HexError> {
+    let angles: Vec<Angle> = get_pattern_segments(direction, pattern)?
+        .iter()
+        .tuple_windows()
+        .map(|(a, b)| b.direction().angle_from(a.direction()))
+        .collect();
+
+    let sign = match angles.get(..4) {
+        Some(prefix) if prefix == POSITIVE_PREFIX => NonZeroSign::Positive,
+        Some(prefix) if prefix == NEGATIVE_PREFIX => NonZeroSign::Negative,
+        _ => return Err(HexError::InvalidNumberPrefix(pattern.to_string())),
+    };
+
+    let mut value = Ratio::from(0);
+    for angle in &angles[4..] {
+        value = angle.apply_to(value)?;
+    }
+
+    let numer = i64::try_from(*value.numer()).map_err(|_| HexError::ValueOutOfRange(value))?;
+    let denom = i64::try_from(*value.denom()).map_err(|_| HexError::ValueOutOfRange(value))?;
+
+    Ok(match sign {
+        NonZeroSign::Positive => Ratio::new(numer, denom),
+        NonZeroSign::Negative => -Ratio::new(numer, denom),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_number_pattern, Algorithm, GeneratorConfig};
+
+    fn decode(direction: &str, pattern: &str) -> Result<Ratio<i64>, HexError> {
+        decode_number_pattern(direction.parse()?, pattern)
+    }
+
+    #[test]
+    fn round_trips_generated_patterns() {
+        for target in [1, 2, 7, 10, 27, 100, 137, -5, -64] {
+            for algorithm in [Algorithm::Beam, Algorithm::AStar] {
+                let number = generate_number_pattern(GeneratorConfig::new(target.into()), algorithm).unwrap();
+                assert_eq!(decode(&number.direction, &number.pattern).unwrap(), target.into(), "{number}");
+            }
+        }
+    }
+
+    #[test]
+    fn decodes_negative_prefix() {
+        assert_eq!(decode("NORTH_EAST", "dedd").unwrap(), 0.into());
+        assert_eq!(decode("NORTH_EAST", "deddw").unwrap(), (-1).into());
+        assert_eq!(decode("NORTH_EAST", "deddwa").unwrap(), (-2).into());
+    }
+
+    #[test]
+    fn decodes_fractions() {
+        assert_eq!(decode("SOUTH_EAST", "aqaawd").unwrap(), Ratio::new(1, 2));
+    }
+
+    #[test]
+    fn rejects_invalid_character() {
+        assert!(matches!(decode("SOUTH_EAST", "aqaax"), Err(HexError::InvalidChar('x'))));
+    }
+
+    #[test]
+    fn rejects_back_angle() {
+        assert!(matches!(decode("SOUTH_EAST", "aqaaws"), Err(HexError::InvalidAngle(Angle::Back))));
+    }
+
+    #[test]
+    fn rejects_invalid_prefix() {
+        assert!(matches!(decode("SOUTH_EAST", "qqqqw"), Err(HexError::InvalidNumberPrefix(_))));
+        assert!(matches!(decode("SOUTH_EAST", "aq"), Err(HexError::InvalidNumberPrefix(_))));
+    }
+
+    #[test]
+    fn rejects_overflow() {
+        let pattern = format!("aqaaw{}", "a".repeat(70));
+        assert!(matches!(decode("EAST", &pattern), Err(HexError::ValueOutOfRange(_))));
+    }
+}
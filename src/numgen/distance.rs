@@ -0,0 +1,80 @@
+use std::collections::VecDeque;
+
+use num_rational::Ratio;
+
+use super::GeneratorConfig;
+use crate::traits::UnsignedAbsRatio;
+
+/// Lower bounds on how many more angles it takes to get from a value to the target, ignoring geometry.
+pub(crate) struct DistanceTable {
+    target: Ratio<u64>,
+    /// Exact distances for every integer up to the target, if those are the only values a path can reach
+    table: Option<Vec<u8>>,
+}
+
+impl DistanceTable {
+    /// Targets above this fall back to [`DistanceTable::growth_bound`] instead of building a table.
+    const MAX_TABLE_TARGET: u64 = 1 << 22;
+
+    pub fn new(config: &GeneratorConfig) -> Self {
+        let target = config.target.unsigned_abs();
+        let table = (config.trim_larger && !config.allow_fractions)
+            .then(|| target.to_integer())
+            .filter(|&target| target <= Self::MAX_TABLE_TARGET)
+            .map(|target| Self::build(target as usize));
+
+        Self { target, table }
+    }
+
+    /// Breadth-first search backwards from the target over the integers `0..=target`, which is every value a path can
+    /// hold when larger values and fractions are both trimmed.
+    fn build(target: usize) -> Vec<u8> {
+        let mut table = vec![u8::MAX; target + 1];
+        let mut queue = VecDeque::from([target]);
+        table[target] = 0;
+
+        while let Some(value) = queue.pop_front() {
+            let distance = table[value] + 1;
+
+            // every value that some angle turns into this one, ie. the inverse of Angle::apply_to
+            let halved = (value % 2 == 0).then_some(value / 2);
+            let doubled = (value * 2 <= target).then_some(value * 2);
+            let added = [1, 5, 10].into_iter().filter_map(|n| value.checked_sub(n));
+
+            for previous in added.chain(halved).chain(doubled) {
+                if table[previous] == u8::MAX {
+                    table[previous] = distance;
+                    queue.push_back(previous);
+                }
+            }
+        }
+
+        table
+    }
+
+    /// The fewest angles needed to get from `value` to the target, or a lower bound on it if there's no table.
+    pub fn steps_left(&self, value: Ratio<u64>) -> usize {
+        let exact =
+            self.table.as_ref().filter(|_| value.is_integer()).and_then(|table| table.get(value.to_integer() as usize));
+        exact.map_or_else(|| self.growth_bound(value), |&distance| distance.into())
+    }
+
+    /// Each angle at most doubles a value or adds 10 to it, and the only way down is halving.
+    fn growth_bound(&self, mut value: Ratio<u64>) -> usize {
+        let mut steps = 0;
+
+        if value < self.target {
+            while value < self.target {
+                value = (value * 2).max(value + 10);
+                steps += 1;
+            }
+        } else {
+            while value > self.target {
+                value /= 2;
+                steps += 1;
+            }
+        }
+
+        steps
+    }
+}
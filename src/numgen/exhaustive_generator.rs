@@ -0,0 +1,207 @@
+use num_rational::Ratio;
+use num_traits::Zero;
+use strum::IntoEnumIterator;
+
+use crate::{hex_math::Angle, traits::UnsignedAbsRatio, utils::NonZeroSign};
+
+use super::{
+    Budget, DistanceTable, GeneratorConfig, Improvement, Optimality, Path, PathGenerator, SearchOutcome, Solutions,
+    StopReason,
+};
+
+/// Iterative-deepening depth-first search over every non-overlapping path, one length limit at a time.
+///
+/// Only paths that provably can't be kept are skipped, so when it finishes the result is exact. It's much slower than
+/// the other generators and is meant for small targets and for checking their results.
+pub struct ExhaustivePathGenerator {
+    target: Ratio<u64>,
+    config: GeneratorConfig,
+    distances: DistanceTable,
+    solutions: Solutions,
+    /// Lowest objective lower bound among paths cut off by the current length limit
+    cut_off_bound: Option<u64>,
+}
+
+impl ExhaustivePathGenerator {
+    pub fn new(config: GeneratorConfig) -> Self {
+        Self {
+            target: config.target.unsigned_abs(),
+            distances: DistanceTable::new(&config),
+            solutions: Solutions::new(&config),
+            cut_off_bound: None,
+            config,
+        }
+    }
+
+    /// Searches every extension of `path` that could reach the target within `max_len` segments.
+    fn search(
+        &mut self,
+        path: &Path,
+        max_len: usize,
+        budget: &mut Budget,
+        on_improvement: &mut dyn FnMut(&Improvement),
+    ) -> Result<(), StopReason> {
+        // depth-first, so there's no frontier
+        if let Some(reason) = budget.exhausted(0) {
+            return Err(reason);
+        }
+        budget.expand(1);
+
+        for angle in Angle::iter() {
+            let Ok(new_path) = path.with_angle(angle) else {
+                continue;
+            };
+            if !self.config.allows(&new_path) {
+                continue;
+            }
+
+            let steps_left = self.distances.steps_left(new_path.value());
+            if !self.solutions.could_improve(&new_path, steps_left) {
+                continue;
+            }
+
+            if new_path.len() + steps_left > max_len {
+                let bound = self.config.objective.lower_bound(&new_path, steps_left);
+                self.cut_off_bound = Some(self.cut_off_bound.map_or(bound, |cut_off| cut_off.min(bound)));
+                continue;
+            }
+
+            if new_path.value() == self.target && self.solutions.offer(&new_path) {
+                on_improvement(&Improvement::new(&new_path, budget.elapsed()));
+            }
+
+            self.search(&new_path, max_len, budget, on_improvement)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl PathGenerator for ExhaustivePathGenerator {
+    fn run_with(&mut self, on_improvement: &mut dyn FnMut(&Improvement)) -> SearchOutcome {
+        let mut budget = Budget::start(self.config.limits.clone());
+        let root = Path::zero_within(NonZeroSign::from(self.config.target), self.config.bounds);
+
+        if self.target.is_zero() {
+            return SearchOutcome::zero(root, &self.config, &budget, on_improvement);
+        }
+
+        let root_steps_left = self.distances.steps_left(root.value());
+        let mut max_len = root.len() + root_steps_left;
+        if let Some(max_length) = self.config.max_length {
+            max_len = max_len.min(max_length);
+        }
+        // everything not yet searched extends a path that was cut off by the last finished length limit
+        let mut unexplored = Some(self.config.objective.lower_bound(&root, root_steps_left));
+        let mut stop_reason = None;
+        let mut optimality = Optimality::exhaustive(&self.config);
+
+        loop {
+            self.cut_off_bound = None;
+            if let Err(reason) = self.search(&root, max_len, &mut budget, on_improvement) {
+                stop_reason = Some(reason);
+                optimality = Optimality::Heuristic;
+                break;
+            }
+
+            unexplored = self.cut_off_bound;
+            if unexplored.is_none() {
+                break;
+            }
+
+            if self.config.max_length.is_some_and(|max_length| max_len >= max_length) {
+                unexplored = None;
+                // longer paths weren't searched, but an epsilon bound still holds for the ones that were
+                if optimality == Optimality::Optimal {
+                    optimality = Optimality::OptimalInBounds;
+                }
+                break;
+            }
+            max_len += 1;
+        }
+
+        SearchOutcome {
+            solutions: self.solutions.paths().to_vec(),
+            stop_reason,
+            optimality,
+            lower_bound: self.solutions.lower_bound(unexplored),
+            seed: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::{
+        decode_number_pattern,
+        numgen::{Algorithm, Bounds, Metric, SearchLimits},
+    };
+
+    fn best_cost(algorithm: Algorithm, config: GeneratorConfig) -> Option<u64> {
+        let (target, objective) = (config.target, config.objective.clone());
+        let outcome = algorithm.generator(config).run();
+        let path = outcome.best()?;
+        let value = decode_number_pattern(path.starting_direction(), &path.pattern()).unwrap();
+        assert_eq!(value, target, "{algorithm} drew the wrong number");
+        Some(objective.cost(path))
+    }
+
+    fn assert_exact_generators_match(metric: Metric, config: GeneratorConfig) {
+        let config = GeneratorConfig { objective: Arc::new(metric), ..config };
+        let expected = best_cost(Algorithm::Exhaustive, config.clone());
+        for algorithm in [Algorithm::AStar, Algorithm::Bidirectional, Algorithm::SmaStar] {
+            let cost = best_cost(algorithm, config.clone());
+            assert_eq!(cost, expected, "{algorithm} for {} under {metric}", config.target);
+        }
+    }
+
+    #[test]
+    fn exact_generators_match_exhaustive() {
+        for metric in Metric::iter() {
+            for target in (1..=30).chain([64, 100, 137, -7, -50]) {
+                assert_exact_generators_match(metric, GeneratorConfig::new(target.into()));
+            }
+        }
+    }
+
+    #[test]
+    fn exact_generators_match_exhaustive_in_small_bounds() {
+        for metric in Metric::iter() {
+            for target in [5, 12, 27, 40, 100] {
+                let config =
+                    GeneratorConfig { bounds: Some(Bounds::new(3, 4, 4)), ..GeneratorConfig::new(target.into()) };
+                assert_exact_generators_match(metric, config);
+            }
+        }
+    }
+
+    #[test]
+    fn keeps_epsilon_bound_at_max_length() {
+        let config = GeneratorConfig {
+            max_length: Some(9),
+            bounds: None,
+            trim_larger: false,
+            allow_fractions: true,
+            ..GeneratorConfig::new(27.into())
+        };
+        let outcome = ExhaustivePathGenerator::new(config.clone()).run();
+        assert_eq!(outcome.optimality, Optimality::OptimalInBounds);
+
+        let config = GeneratorConfig { epsilon: 1.5, ..config };
+        let outcome = ExhaustivePathGenerator::new(config).run();
+        assert_eq!(outcome.optimality, Optimality::Bounded);
+    }
+
+    #[test]
+    fn ignores_max_frontier() {
+        let limits = SearchLimits { max_frontier: Some(10), ..SearchLimits::default() };
+        let outcome = ExhaustivePathGenerator::new(GeneratorConfig { limits, ..GeneratorConfig::new(27.into()) }).run();
+        assert_eq!(outcome.stop_reason, None);
+        assert!(outcome.best().is_some());
+    }
+}
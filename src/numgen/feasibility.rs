@@ -0,0 +1,236 @@
+use std::{collections::HashMap, sync::Arc};
+
+use num_rational::Ratio;
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+use crate::{
+    errors::HexError,
+    hex_math::{Angle, Direction},
+    traits::UnsignedAbsRatio,
+    utils::NonZeroSign,
+};
+
+use super::{
+    Bounds, Budget, Collect, DistanceTable, GeneratorConfig, Metric, MinMax, Path, StopReason, TranspositionTable,
+};
+
+/// A reason a [`FeasibilityCheck`] gave up on a partial path without searching its extensions. Displays as eg.
+/// `segments-left`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumIter)]
+#[strum(serialize_all = "kebab-case")]
+pub enum PruningRule {
+    /// The new segment was already drawn
+    Overlap,
+    /// The new segment left the bounds
+    OutOfBounds,
+    /// Reaching the target takes more angles than there are undrawn segments left within reach in the bounds
+    SegmentsLeft,
+    /// A path whose every extension was already ruled out had the same value and end segment, and drew a subset of the
+    /// same segments
+    Dominated,
+}
+
+/// Whether any pattern fits, according to a [`FeasibilityCheck`].
+#[derive(Clone)]
+pub enum Feasibility {
+    /// A path that draws the target within the bounds
+    Feasible(Path),
+    /// Every path was ruled out, so no pattern fits
+    Infeasible,
+    /// The search stopped before it was finished
+    Unknown(StopReason),
+}
+
+/// The result of [`FeasibilityCheck::run`].
+#[derive(Clone)]
+pub struct FeasibilityReport {
+    pub feasibility: Feasibility,
+    /// Number of paths expanded
+    pub expanded: usize,
+    /// How many paths each rule ruled out, for the rules that were used
+    pub pruned: Vec<(PruningRule, usize)>,
+}
+
+/// Depth-first search over every path in some bounds, which either finds one that draws the target or proves that none
+/// does, unlike the generators, which can only say they didn't find one.
+///
+/// Only the [`PruningRule`]s are used to skip paths, and each of them only skips paths that can't reach the target.
+/// Paths are only remembered as dead ends once every extension was ruled out, so dominated paths are skipped if
+/// [`GeneratorConfig::transpositions`] is set without relying on anything that wasn't proven yet.
+///
+/// Rotating a pattern permutes its extents, so the search starts from each rotation of the prefix that fits the bounds
+/// differently.
+pub struct FeasibilityCheck {
+    target: Ratio<u64>,
+    bounds: Bounds,
+    config: GeneratorConfig,
+    distances: DistanceTable,
+    dead_ends: TranspositionTable,
+    /// Number of segments within each set of extents a path can reach
+    room: HashMap<MinMax, usize>,
+    pruned: HashMap<PruningRule, usize>,
+}
+
+impl FeasibilityCheck {
+    /// Checks `bounds` instead of the config's own bounds. The config's transpositions and limits apply, but not
+    /// `trim_larger` or `allow_fractions`, since a valid pattern can pass through larger and fractional values.
+    pub fn new(config: GeneratorConfig, bounds: Bounds) -> Self {
+        // one witness is enough, so no path needs to be kept for being distinct or for its cost
+        let config = GeneratorConfig {
+            bounds: Some(bounds),
+            trim_larger: false,
+            allow_fractions: true,
+            collect: Collect::Best,
+            objective: Arc::new(Metric::Length),
+            ..config
+        };
+        Self {
+            target: config.target.unsigned_abs(),
+            bounds,
+            distances: DistanceTable::new(&config),
+            dead_ends: TranspositionTable::new(&config),
+            room: HashMap::new(),
+            pruned: HashMap::new(),
+            config,
+        }
+    }
+
+    /// Number of segments a path can still draw without leaving the bounds.
+    fn room_left(&mut self, path: &Path) -> usize {
+        let reach = path.minmax().reach_within(self.bounds);
+        let room = *self.room.entry(reach).or_insert_with(|| {
+            reach
+                .coords()
+                .flat_map(|coord| [Direction::NorthEast, Direction::East, Direction::SouthEast].map(|d| coord + d))
+                .filter(|&end| reach.contains(end))
+                .count()
+        });
+        room.saturating_sub(path.len())
+    }
+
+    fn rule_out(&mut self, path: &Path) -> Option<PruningRule> {
+        if !path.bounds().fits_in(self.bounds) {
+            Some(PruningRule::OutOfBounds)
+        } else if self.distances.steps_left(path.value()) > self.room_left(path) {
+            Some(PruningRule::SegmentsLeft)
+        } else if self.dead_ends.is_dominated(path) {
+            Some(PruningRule::Dominated)
+        } else {
+            None
+        }
+    }
+
+    /// Searches every extension of `path`, returning the first one that draws the target.
+    fn search(&mut self, path: &Path, budget: &mut Budget) -> Result<Option<Path>, StopReason> {
+        // depth-first, so there's no frontier
+        if let Some(reason) = budget.exhausted(0) {
+            return Err(reason);
+        }
+        budget.expand(1);
+
+        for angle in Angle::iter() {
+            let rule = match path.with_angle(angle) {
+                Ok(new_path) => match self.rule_out(&new_path) {
+                    None if new_path.value() == self.target => return Ok(Some(new_path)),
+                    None => match self.search(&new_path, budget)? {
+                        Some(witness) => return Ok(Some(witness)),
+                        None => {
+                            self.dead_ends.insert(&new_path);
+                            continue;
+                        }
+                    },
+                    Some(rule) => rule,
+                },
+                Err(HexError::SegmentAlreadyExists(_)) => PruningRule::Overlap,
+                Err(HexError::SegmentOutOfBounds(_)) => PruningRule::OutOfBounds,
+                // not an angle numbers use
+                Err(_) => continue,
+            };
+            *self.pruned.entry(rule).or_default() += 1;
+        }
+
+        Ok(None)
+    }
+
+    pub fn run(&mut self) -> FeasibilityReport {
+        let mut budget = Budget::start(self.config.limits.clone());
+        let sign = NonZeroSign::from(self.config.target);
+        let usual = Path::zero(sign).starting_direction();
+
+        // turning 180 degrees keeps the extents the same, and each 60 degree turn permutes them
+        let (q, r, s) = (self.bounds.q(), self.bounds.r(), self.bounds.s());
+        let turns =
+            if q == r && r == s { &[Angle::Forward][..] } else { &[Angle::Forward, Angle::Right, Angle::RightBack] };
+
+        let mut feasibility = Feasibility::Infeasible;
+        for &turn in turns {
+            let root = Path::zero_facing(sign, usual.rotated(turn), self.config.bounds);
+            let found = if !root.bounds().fits_in(self.bounds) {
+                *self.pruned.entry(PruningRule::OutOfBounds).or_default() += 1;
+                Ok(None)
+            } else if root.value() == self.target {
+                Ok(Some(root))
+            } else {
+                self.search(&root, &mut budget)
+            };
+
+            match found {
+                Ok(Some(witness)) => feasibility = Feasibility::Feasible(witness),
+                Ok(None) => continue,
+                Err(reason) => feasibility = Feasibility::Unknown(reason),
+            }
+            break;
+        }
+
+        FeasibilityReport {
+            feasibility,
+            expanded: budget.expanded(),
+            pruned: PruningRule::iter().filter_map(|rule| Some((rule, *self.pruned.get(&rule)?))).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode_number_pattern, numgen::SearchLimits};
+
+    fn check(target: i64, bounds: Bounds) -> Feasibility {
+        let config = GeneratorConfig::new(target.into());
+        FeasibilityCheck::new(config, bounds).run().feasibility
+    }
+
+    fn assert_feasible(target: i64, bounds: Bounds) {
+        let Feasibility::Feasible(path) = check(target, bounds) else {
+            panic!("{target} should fit in {bounds:?}");
+        };
+        assert!(path.bounds().fits_in(bounds), "{target} doesn't fit in {bounds:?}");
+        let value = decode_number_pattern(path.starting_direction(), &path.pattern()).unwrap();
+        assert_eq!(value, target.into());
+    }
+
+    #[test]
+    fn ignores_value_rules() {
+        // SOUTH_EAST aqaaedw goes through 10
+        assert_feasible(6, Bounds::from(3));
+    }
+
+    #[test]
+    fn tries_every_rotation() {
+        for (target, bounds) in [(10, Bounds::new(3, 2, 3)), (12, Bounds::new(4, 2, 4)), (27, Bounds::new(3, 3, 4))] {
+            assert_feasible(target, bounds);
+        }
+    }
+
+    #[test]
+    fn proves_infeasible() {
+        assert!(matches!(check(1234, Bounds::new(3, 5, 4)), Feasibility::Infeasible));
+    }
+
+    #[test]
+    fn ignores_max_frontier() {
+        let limits = SearchLimits { max_frontier: Some(10), ..SearchLimits::default() };
+        let config = GeneratorConfig { limits, ..GeneratorConfig::new(27.into()) };
+        assert!(matches!(FeasibilityCheck::new(config, Bounds::from(4)).run().feasibility, Feasibility::Feasible(_)));
+    }
+}
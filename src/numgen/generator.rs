@@ -0,0 +1,122 @@
+use strum::{Display, EnumIter, EnumString};
+
+use super::{
+    AStarPathGenerator, BeamPathGenerator, BidirectionalPathGenerator, Budget, Collect, ExhaustivePathGenerator,
+    GeneratorConfig, Improvement, Objective, Path, SmaStarPathGenerator, StopReason,
+};
+
+/// A search algorithm that finds the smallest path drawing a target number.
+pub trait PathGenerator {
+    /// Runs the search, calling `on_improvement` every time a new smallest path is found.
+    fn run_with(&mut self, on_improvement: &mut dyn FnMut(&Improvement)) -> SearchOutcome;
+
+    fn run(&mut self) -> SearchOutcome {
+        self.run_with(&mut |_| {})
+    }
+}
+
+/// The result of [`PathGenerator::run`].
+#[derive(Clone)]
+pub struct SearchOutcome {
+    /// The paths kept according to [`GeneratorConfig::collect`], best first
+    pub solutions: Vec<Path>,
+    /// Why the search stopped before it was finished, if it did
+    pub stop_reason: Option<StopReason>,
+    pub optimality: Optimality,
+    /// Seed of the beam search run that found the best path, which reproduces it exactly with no restarts
+    pub seed: Option<u64>,
+    /// No finished path in the search space costs less than this under the objective. `None` if the whole space was
+    /// searched without finding any path
+    pub lower_bound: Option<u64>,
+}
+
+impl SearchOutcome {
+    /// The outcome for a target of zero, which `root` (the prefix alone) draws as well as any path could.
+    pub(crate) fn zero(
+        root: Path,
+        config: &GeneratorConfig,
+        budget: &Budget,
+        on_improvement: &mut dyn FnMut(&Improvement),
+    ) -> Self {
+        on_improvement(&Improvement::new(&root, budget.elapsed()));
+        Self {
+            lower_bound: Some(config.objective.cost(&root)),
+            solutions: vec![root],
+            stop_reason: None,
+            optimality: Optimality::Optimal,
+            seed: None,
+        }
+    }
+
+    /// The best path found under the objective, if any
+    pub fn best(&self) -> Option<&Path> {
+        self.solutions.first()
+    }
+
+    pub fn stopped_early(&self) -> bool {
+        self.stop_reason.is_some()
+    }
+
+    /// How many times more than the best possible path the best path found could cost under `objective`, going by
+    /// the lower bound. `Some(1.0)` if it's optimal, `None` if nothing was found or the bound is zero.
+    pub fn within_factor(&self, objective: &dyn Objective) -> Option<f64> {
+        let cost = objective.cost(self.best()?);
+        match self.lower_bound? {
+            lower_bound if lower_bound >= cost => Some(1.0),
+            0 => None,
+            lower_bound => Some(cost as f64 / lower_bound as f64),
+        }
+    }
+}
+
+/// How sure a search is that its best path can't be beaten. Displays as eg. `optimal-in-bounds`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Optimality {
+    /// Nothing was skipped except paths that couldn't beat the best one, so no better pattern exists
+    Optimal,
+    /// Like [`Optimality::Optimal`], but only among paths allowed by the config's bounds, `trim_larger` and
+    /// `allow_fractions`
+    OptimalInBounds,
+    /// Like [`Optimality::OptimalInBounds`], but paths that couldn't beat the best one by more than
+    /// [`GeneratorConfig::epsilon`] were skipped too, so a better pattern may exist within that factor
+    Bounded,
+    /// Some paths were dropped or never explored, so a better pattern may exist
+    Heuristic,
+}
+
+impl Optimality {
+    /// The optimality of a search that explored every path in its search space that could beat the best one.
+    pub(crate) fn exhaustive(config: &GeneratorConfig) -> Self {
+        if config.epsilon > 1.0 && config.collect != Collect::ParetoFront {
+            Optimality::Bounded
+        } else if config.bounds.is_some() || config.trim_larger || !config.allow_fractions {
+            Optimality::OptimalInBounds
+        } else {
+            Optimality::Optimal
+        }
+    }
+}
+
+/// Selects a [`PathGenerator`] at runtime. Parses from and displays as eg. `astar`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumIter, EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum Algorithm {
+    Beam,
+    AStar,
+    Exhaustive,
+    Bidirectional,
+    SmaStar,
+}
+
+impl Algorithm {
+    pub fn generator(self, config: GeneratorConfig) -> Box<dyn PathGenerator + Send> {
+        match self {
+            Algorithm::Beam => Box::new(BeamPathGenerator::new(config)),
+            Algorithm::AStar => Box::new(AStarPathGenerator::new(config)),
+            Algorithm::Exhaustive => Box::new(ExhaustivePathGenerator::new(config)),
+            Algorithm::Bidirectional => Box::new(BidirectionalPathGenerator::new(config)),
+            Algorithm::SmaStar => Box::new(SmaStarPathGenerator::new(config)),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use std::{
+    sync::mpsc::{self, Receiver},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use super::{Algorithm, Bounds, CancellationToken, GeneratorConfig, Path, SearchOutcome};
+
+/// A new smallest path found while a search is still running.
+#[derive(Clone)]
+pub struct Improvement {
+    pub path: Path,
+    pub bounds: Bounds,
+    /// Time since the search started
+    pub elapsed: Duration,
+}
+
+impl Improvement {
+    pub(crate) fn new(path: &Path, elapsed: Duration) -> Self {
+        Self { path: path.clone(), bounds: path.bounds(), elapsed }
+    }
+}
+
+/// Runs a search on a background thread, yielding each [`Improvement`] as soon as it's found.
+///
+/// Dropping the iterator before the search finishes cancels it. If the config already has a [`CancellationToken`],
+/// that token is the one that gets cancelled.
+pub struct Improvements {
+    receiver: Receiver<Improvement>,
+    handle: Option<JoinHandle<SearchOutcome>>,
+    cancellation: CancellationToken,
+}
+
+impl Improvements {
+    pub fn spawn(algorithm: Algorithm, mut config: GeneratorConfig) -> Self {
+        let cancellation = config.limits.cancellation.get_or_insert_with(CancellationToken::new).clone();
+        let (sender, receiver) = mpsc::channel();
+
+        let handle = thread::spawn(move || {
+            algorithm.generator(config).run_with(&mut |improvement| {
+                // the receiver only goes away once we've been cancelled, so there's nothing to do on failure
+                let _ = sender.send(improvement.clone());
+            })
+        });
+
+        Self { receiver, handle: Some(handle), cancellation }
+    }
+
+    /// Waits for the search to finish and returns its outcome.
+    pub fn finish(mut self) -> SearchOutcome {
+        self.handle.take().unwrap().join().unwrap()
+    }
+}
+
+impl Iterator for Improvements {
+    type Item = Improvement;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.receiver.recv().ok()
+    }
+}
+
+impl Drop for Improvements {
+    fn drop(&mut self) {
+        if self.handle.is_some() {
+            self.cancellation.cancel();
+        }
+    }
+}
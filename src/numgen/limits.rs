@@ -0,0 +1,92 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use strum::Display;
+
+/// Shared flag for stopping a search from another thread. Clones refer to the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Limits after which a search gives up and returns the best path found so far.
+#[derive(Debug, Clone, Default)]
+pub struct SearchLimits {
+    pub time: Option<Duration>,
+    /// Maximum number of paths expanded
+    pub max_expanded: Option<usize>,
+    /// Maximum number of paths waiting to be expanded
+    pub max_frontier: Option<usize>,
+    pub cancellation: Option<CancellationToken>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum StopReason {
+    #[strum(serialize = "time limit reached")]
+    TimeLimit,
+    #[strum(serialize = "expanded node limit reached")]
+    NodeLimit,
+    #[strum(serialize = "frontier size limit reached")]
+    FrontierLimit,
+    #[strum(serialize = "cancelled")]
+    Cancelled,
+    /// Some paths were too long to keep in memory along with every path they extend (SMA* only)
+    #[strum(serialize = "max nodes too small to hold every path")]
+    MaxNodes,
+}
+
+/// Tracks a running search against its [`SearchLimits`].
+pub(crate) struct Budget {
+    limits: SearchLimits,
+    start: Instant,
+    expanded: usize,
+}
+
+impl Budget {
+    pub fn start(limits: SearchLimits) -> Self {
+        Self { limits, start: Instant::now(), expanded: 0 }
+    }
+
+    pub fn expand(&mut self, count: usize) {
+        self.expanded += count;
+    }
+
+    pub fn expanded(&self) -> usize {
+        self.expanded
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    pub fn exhausted(&self, frontier_len: usize) -> Option<StopReason> {
+        if self.limits.cancellation.as_ref().is_some_and(CancellationToken::is_cancelled) {
+            Some(StopReason::Cancelled)
+        } else if self.limits.time.is_some_and(|time| self.start.elapsed() >= time) {
+            Some(StopReason::TimeLimit)
+        } else if self.limits.max_expanded.is_some_and(|max| self.expanded >= max) {
+            Some(StopReason::NodeLimit)
+        } else if self.limits.max_frontier.is_some_and(|max| frontier_len > max) {
+            Some(StopReason::FrontierLimit)
+        } else {
+            None
+        }
+    }
+}
@@ -4,7 +4,7 @@ use crate::hex_math::{Coord, Segment};
 
 use super::Bounds;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct MinMax {
     min_q: i32,
     max_q: i32,
@@ -25,6 +25,43 @@ impl MinMax {
             max_s: max(self.max_s, point.s()),
         }
     }
+
+    /// Whether this fits inside `other` without moving.
+    pub fn within(&self, other: &MinMax) -> bool {
+        self.min_q >= other.min_q
+            && self.max_q <= other.max_q
+            && self.min_r >= other.min_r
+            && self.max_r <= other.max_r
+            && self.min_s >= other.min_s
+            && self.max_s <= other.max_s
+    }
+
+    /// The largest extents around these that still fit in `bounds`, ie. everywhere a path with these extents can reach
+    /// without leaving them.
+    pub fn reach_within(&self, bounds: Bounds) -> Self {
+        let (q, r, s) = (bounds.q() as i32 - 1, bounds.r() as i32 - 1, bounds.s() as i32 - 1);
+        Self {
+            min_q: self.max_q - q,
+            max_q: self.min_q + q,
+            min_r: self.max_r - r,
+            max_r: self.min_r + r,
+            min_s: self.max_s - s,
+            max_s: self.min_s + s,
+        }
+    }
+
+    pub fn contains(&self, point: Coord) -> bool {
+        (self.min_q..=self.max_q).contains(&point.q())
+            && (self.min_r..=self.max_r).contains(&point.r())
+            && (self.min_s..=self.max_s).contains(&point.s())
+    }
+
+    /// Every coord within these extents.
+    pub fn coords(&self) -> impl Iterator<Item = Coord> + '_ {
+        (self.min_q..=self.max_q)
+            .flat_map(|q| (self.min_r..=self.max_r).map(move |r| Coord::new(q, r)))
+            .filter(|&coord| self.contains(coord))
+    }
 }
 
 impl From<&Vec<Segment>> for MinMax {
@@ -0,0 +1,109 @@
+use std::str::FromStr;
+
+use strum::{Display, EnumIter, EnumString};
+
+use super::Path;
+use crate::errors::HexError;
+
+/// Decides what "smallest" means when comparing paths. Lower costs are better.
+///
+/// Costs are also used to prune partial paths, so a path's cost must never decrease as segments are added to it.
+/// Any `Fn(&Path) -> u64` closure can be used as a custom objective.
+pub trait Objective: Send + Sync {
+    fn cost(&self, path: &Path) -> u64;
+
+    /// A lower bound on the cost of any finished path that `path` could be extended into, given that it needs at least
+    /// `steps_left` more segments.
+    fn lower_bound(&self, path: &Path, _steps_left: usize) -> u64 {
+        self.cost(path)
+    }
+
+    /// Whether a path never costs less than one that dominates it, ie. one with the same value and end that draws a
+    /// subset of its segments within the same bounds. Dominated paths are only skipped when this is true, which it isn't
+    /// for closures.
+    fn supports_dominance(&self) -> bool {
+        false
+    }
+}
+
+impl<F> Objective for F
+where
+    F: Fn(&Path) -> u64 + Send + Sync,
+{
+    fn cost(&self, path: &Path) -> u64 {
+        self(path)
+    }
+}
+
+/// A single measurement of a path. Parses from and displays as eg. `quasi-area`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumIter, EnumString)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Metric {
+    /// Number of segments, ie. strokes
+    Length,
+    QuasiArea,
+    LargestDimension,
+    NumPoints,
+}
+
+impl Metric {
+    pub fn of(self, path: &Path) -> u64 {
+        match self {
+            Metric::Length => path.len() as u64,
+            Metric::QuasiArea => path.bounds().quasi_area().into(),
+            Metric::LargestDimension => path.bounds().largest_dimension().into(),
+            Metric::NumPoints => path.num_points() as u64,
+        }
+    }
+}
+
+impl Objective for Metric {
+    fn cost(&self, path: &Path) -> u64 {
+        self.of(path)
+    }
+
+    fn lower_bound(&self, path: &Path, steps_left: usize) -> u64 {
+        match self {
+            Metric::Length => (path.len() + steps_left) as u64,
+            _ => self.of(path),
+        }
+    }
+
+    fn supports_dominance(&self) -> bool {
+        true
+    }
+}
+
+/// A weighted sum of metrics. Parses from eg. `length=2,quasi-area=1`, where a metric without a weight counts once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Weighted(pub Vec<(Metric, u64)>);
+
+impl Objective for Weighted {
+    fn cost(&self, path: &Path) -> u64 {
+        self.0.iter().map(|&(metric, weight)| metric.of(path) * weight).sum()
+    }
+
+    fn lower_bound(&self, path: &Path, steps_left: usize) -> u64 {
+        self.0.iter().map(|&(metric, weight)| metric.lower_bound(path, steps_left) * weight).sum()
+    }
+
+    fn supports_dominance(&self) -> bool {
+        true
+    }
+}
+
+impl FromStr for Weighted {
+    type Err = HexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || HexError::InvalidObjective(s.to_string());
+
+        s.split(',')
+            .map(|term| {
+                let (metric, weight) = term.split_once('=').unwrap_or((term, "1"));
+                Ok((metric.trim().parse().map_err(|_| invalid())?, weight.trim().parse().map_err(|_| invalid())?))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
@@ -0,0 +1,279 @@
+use crate::hex_math::{Coord, Segment};
+
+use super::Bounds;
+
+/// Largest window that gets a grid, which is 512 bytes. Every path copies its parent's grid, so larger bounds use the
+/// filters instead.
+const MAX_GRID_COORDS: usize = 1024;
+
+/// Every coord that a path fitting in some bounds can reach. Paths start at the origin, so that's everything within
+/// one less than the size along each axis.
+#[derive(Clone, Copy)]
+pub(crate) struct Window {
+    q_radius: i32,
+    r_radius: i32,
+}
+
+impl Window {
+    fn new(bounds: Bounds) -> Option<Self> {
+        (bounds.q() > 0 && bounds.r() > 0)
+            .then(|| Self { q_radius: bounds.q() as i32 - 1, r_radius: bounds.r() as i32 - 1 })
+    }
+
+    fn height(&self) -> usize {
+        (2 * self.r_radius + 1) as usize
+    }
+
+    fn num_coords(&self) -> usize {
+        (2 * self.q_radius + 1) as usize * self.height()
+    }
+
+    fn coord_index(&self, coord: Coord) -> Option<usize> {
+        let (q, r) = (coord.q() + self.q_radius, coord.r() + self.r_radius);
+        (0..=2 * self.q_radius).contains(&q).then_some(())?;
+        (0..=2 * self.r_radius).contains(&r).then_some(())?;
+        Some(q as usize * self.height() + r as usize)
+    }
+
+    /// Segments come first, three per coord, followed by the points.
+    fn segment_bit(&self, segment: Segment) -> Option<usize> {
+        Some(self.coord_index(segment.canonical_root())? * 3 + segment.canonical_direction() as usize)
+    }
+
+    fn point_bit(&self, point: Coord) -> Option<usize> {
+        Some(self.num_coords() * 3 + self.coord_index(point)?)
+    }
+}
+
+/// A 128 bit Bloom filter over packed segments or coords. A clear bit means an item is definitely missing.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct Filter(u128);
+
+impl Filter {
+    fn bit(packed: u64) -> u128 {
+        1 << (packed.wrapping_mul(0x9e37_79b9_7f4a_7c15) >> 57)
+    }
+
+    fn with(self, packed: u64) -> Self {
+        Self(self.0 | Self::bit(packed))
+    }
+
+    fn may_contain(self, packed: u64) -> bool {
+        self.0 & Self::bit(packed) != 0
+    }
+
+    fn may_be_subset_of(self, other: Filter) -> bool {
+        self.0 & !other.0 == 0
+    }
+}
+
+/// Which segments and points a path has drawn.
+#[derive(Clone)]
+pub(crate) enum Occupancy {
+    /// One bit per segment and point in the window, so lookups are exact
+    Grid { window: Window, bits: Box<[u64]> },
+    /// For unbounded searches, where anything the filters don't rule out has to be checked against the path itself
+    Filtered { segments: Filter, points: Filter },
+}
+
+/// The answer to an [`Occupancy`] lookup.
+pub(crate) enum Lookup {
+    Absent,
+    Present,
+    /// Only the filters were checked, so the path has to be searched
+    Maybe,
+    /// Outside the grid, so the path can't fit in its bounds
+    OutOfBounds,
+}
+
+impl Occupancy {
+    /// Occupancy for the given segments. Uses a grid sized from `bounds` if they're given, they're small enough, and the
+    /// segments fit.
+    pub fn new(bounds: Option<Bounds>, segments: &[Segment]) -> Self {
+        let empty = bounds
+            .and_then(Window::new)
+            .filter(|window| window.num_coords() <= MAX_GRID_COORDS)
+            .map_or(Occupancy::Filtered { segments: Filter::default(), points: Filter::default() }, |window| {
+                Occupancy::Grid { window, bits: vec![0; (window.num_coords() * 4).div_ceil(64)].into() }
+            });
+
+        empty
+            .with_point(segments[0].root())
+            .and_then(|occupancy| segments.iter().try_fold(occupancy, |occupancy, &segment| occupancy.with(segment)))
+            // the segments don't fit in the bounds, so the grid would be no use
+            .unwrap_or_else(|| Self::new(None, segments))
+    }
+
+    fn with_point(&self, point: Coord) -> Option<Self> {
+        Some(match self {
+            Occupancy::Grid { window, bits } => {
+                let mut bits = bits.clone();
+                let i = window.point_bit(point)?;
+                bits[i / 64] |= 1 << (i % 64);
+                Occupancy::Grid { window: *window, bits }
+            }
+            Occupancy::Filtered { segments, points } => {
+                Occupancy::Filtered { segments: *segments, points: points.with(point.packed().into()) }
+            }
+        })
+    }
+
+    /// Marks `segment` and its end as drawn, or returns `None` if either is outside the grid.
+    pub fn with(&self, segment: Segment) -> Option<Self> {
+        Some(match self {
+            Occupancy::Grid { window, bits } => {
+                let mut bits = bits.clone();
+                for i in [window.segment_bit(segment)?, window.point_bit(segment.end())?] {
+                    bits[i / 64] |= 1 << (i % 64);
+                }
+                Occupancy::Grid { window: *window, bits }
+            }
+            Occupancy::Filtered { segments, points } => Occupancy::Filtered {
+                segments: segments.with(segment.packed()),
+                points: points.with(segment.end().packed().into()),
+            },
+        })
+    }
+
+    fn lookup(bits: &[u64], i: Option<usize>) -> Lookup {
+        match i {
+            Some(i) if bits[i / 64] & 1 << (i % 64) != 0 => Lookup::Present,
+            Some(_) => Lookup::Absent,
+            None => Lookup::OutOfBounds,
+        }
+    }
+
+    pub fn segment(&self, segment: Segment) -> Lookup {
+        match self {
+            Occupancy::Grid { window, bits } => Self::lookup(bits, window.segment_bit(segment)),
+            Occupancy::Filtered { segments, .. } if segments.may_contain(segment.packed()) => Lookup::Maybe,
+            Occupancy::Filtered { .. } => Lookup::Absent,
+        }
+    }
+
+    pub fn point(&self, point: Coord) -> Lookup {
+        match self {
+            Occupancy::Grid { window, bits } => Self::lookup(bits, window.point_bit(point)),
+            Occupancy::Filtered { points, .. } if points.may_contain(point.packed().into()) => Lookup::Maybe,
+            Occupancy::Filtered { .. } => Lookup::Absent,
+        }
+    }
+
+    /// Whether every segment drawn here is also drawn in `other`, or `None` if the paths have to be compared to know.
+    pub fn is_subset_of(&self, other: &Occupancy) -> Option<bool> {
+        match (self, other) {
+            (Occupancy::Grid { bits, .. }, Occupancy::Grid { bits: other_bits, .. }) => {
+                Some(bits.iter().zip(other_bits.iter()).all(|(bits, other_bits)| bits & !other_bits == 0))
+            }
+            (Occupancy::Filtered { segments, .. }, Occupancy::Filtered { segments: other_segments, .. })
+                if !segments.may_be_subset_of(*other_segments) =>
+            {
+                Some(false)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::hex_math::Direction;
+
+    fn start() -> Vec<Segment> {
+        vec![Segment::new(Coord::origin(), Direction::East)]
+    }
+
+    fn is_present(lookup: Lookup) -> bool {
+        matches!(lookup, Lookup::Present | Lookup::Maybe)
+    }
+
+    #[test]
+    fn packs_negative_coords_uniquely() {
+        let range = -3..=3;
+        let coords: Vec<_> = range.clone().flat_map(|q| range.clone().map(move |r| Coord::new(q, r))).collect();
+        let packed: HashSet<_> = coords.iter().map(Coord::packed).collect();
+        assert_eq!(packed.len(), coords.len());
+        assert_ne!(Coord::new(i16::MIN.into(), i16::MAX.into()).packed(), Coord::new(i16::MAX.into(), 0).packed());
+    }
+
+    #[test]
+    fn packs_segments_either_way_round() {
+        let segment = Segment::new(Coord::new(-4, 2), Direction::SouthWest);
+        let reversed = Segment::new(segment.end(), Direction::NorthEast);
+        assert_eq!(segment.packed(), reversed.packed());
+        assert_ne!(segment.packed(), Segment::new(segment.end(), Direction::East).packed());
+    }
+
+    #[test]
+    fn grid_marks_segments_at_window_edges() {
+        // the window reaches two coords out from the origin in each direction
+        let occupancy = Occupancy::new(Some(Bounds::from(3)), &start());
+        assert!(matches!(occupancy, Occupancy::Grid { .. }));
+
+        let corners = [
+            Segment::new(Coord::new(1, -2), Direction::East),
+            Segment::new(Coord::new(-1, 2), Direction::West),
+            Segment::new(Coord::new(-2, 1), Direction::NorthEast),
+            Segment::new(Coord::new(2, -1), Direction::SouthWest),
+        ];
+        for segment in corners {
+            assert!(matches!(occupancy.segment(segment), Lookup::Absent));
+            let marked = occupancy.with(segment).unwrap();
+            assert!(matches!(marked.segment(segment), Lookup::Present));
+            assert!(matches!(marked.point(segment.end()), Lookup::Present));
+            assert!(matches!(marked.segment(Segment::new(Coord::origin(), Direction::West)), Lookup::Absent));
+        }
+    }
+
+    #[test]
+    fn grid_rejects_segments_outside_the_window() {
+        let occupancy = Occupancy::new(Some(Bounds::from(3)), &start());
+        for segment in [
+            Segment::new(Coord::new(2, 0), Direction::East),
+            Segment::new(Coord::new(-2, 0), Direction::West),
+            Segment::new(Coord::new(0, -2), Direction::NorthEast),
+        ] {
+            assert!(occupancy.with(segment).is_none());
+            assert!(matches!(occupancy.point(segment.end()), Lookup::OutOfBounds));
+        }
+        let outside = Segment::new(Coord::new(-3, 0), Direction::East);
+        assert!(matches!(occupancy.segment(outside), Lookup::OutOfBounds));
+    }
+
+    #[test]
+    fn large_bounds_use_filters() {
+        assert!(matches!(Occupancy::new(Some(Bounds::from(100)), &start()), Occupancy::Filtered { .. }));
+        assert!(matches!(Occupancy::new(None, &start()), Occupancy::Filtered { .. }));
+    }
+
+    #[test]
+    fn filters_have_no_false_negatives() {
+        let empty = Occupancy::new(None, &start());
+        assert!(matches!(empty.segment(Segment::new(Coord::new(-5, 3), Direction::East)), Lookup::Absent));
+
+        let segments: Vec<_> = (-10..=10)
+            .flat_map(|q| (-10..=10).map(move |r| Coord::new(q * 7, r * 5)))
+            .flat_map(|root| {
+                [Direction::NorthEast, Direction::East, Direction::SouthWest]
+                    .map(move |direction| Segment::new(root, direction))
+            })
+            .collect();
+        let occupancy = segments.iter().try_fold(empty, |occupancy, &segment| occupancy.with(segment)).unwrap();
+
+        for segment in segments {
+            assert!(is_present(occupancy.segment(segment)), "{segment:?}");
+            assert!(is_present(occupancy.point(segment.end())), "{segment:?}");
+        }
+    }
+
+    #[test]
+    fn grid_subsets() {
+        let occupancy = Occupancy::new(Some(Bounds::from(3)), &start());
+        let extended = occupancy.with(Segment::new(Coord::new(1, 0), Direction::SouthEast)).unwrap();
+        assert_eq!(occupancy.is_subset_of(&extended), Some(true));
+        assert_eq!(extended.is_subset_of(&occupancy), Some(false));
+    }
+}
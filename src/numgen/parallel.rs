@@ -0,0 +1,96 @@
+use std::thread;
+
+/// Splits `items` into one contiguous chunk per thread. Chunks are at least `min_chunk` long, so small inputs don't pay
+/// for threads they can't use.
+fn chunks<T>(items: &[T], threads: usize, min_chunk: usize) -> impl Iterator<Item = &[T]> {
+    let size = items.len().div_ceil(threads.max(1)).max(min_chunk).max(1);
+    items.chunks(size)
+}
+
+/// Maps `f` over `items` on up to `threads` threads. The results are in the same order as `items`, so they don't depend
+/// on the thread count.
+pub(crate) fn map<T, R, F>(items: &[T], threads: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    const MIN_CHUNK: usize = 16;
+
+    if threads <= 1 || items.len() <= MIN_CHUNK {
+        return items.iter().map(f).collect();
+    }
+
+    thread::scope(|scope| {
+        let f = &f;
+        let handles: Vec<_> = chunks(items, threads, MIN_CHUNK)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(f).collect::<Vec<_>>()))
+            .collect();
+        handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+    })
+}
+
+/// A stable sort by key on up to `threads` threads, giving exactly the same order as [`slice::sort_by_key`].
+pub(crate) fn sort_by_key<T, K, F>(items: Vec<T>, threads: usize, f: F) -> Vec<T>
+where
+    T: Send,
+    K: Ord,
+    F: Fn(&T) -> K + Sync,
+{
+    const MIN_CHUNK: usize = 1024;
+
+    let mut items = items;
+    if threads <= 1 || items.len() <= MIN_CHUNK {
+        items.sort_by_key(f);
+        return items;
+    }
+
+    let size = items.len().div_ceil(threads).max(MIN_CHUNK);
+    let mut sorted = Vec::new();
+    while items.len() > size {
+        let rest = items.split_off(size);
+        sorted.push(items);
+        items = rest;
+    }
+    sorted.push(items);
+
+    let mut sorted: Vec<_> = thread::scope(|scope| {
+        let f = &f;
+        let handles: Vec<_> = sorted
+            .into_iter()
+            .map(|mut chunk| {
+                scope.spawn(move || {
+                    chunk.sort_by_key(f);
+                    chunk
+                })
+            })
+            .collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+
+    // chunks are merged in order and ties go to the earlier one, which keeps the sort stable
+    while sorted.len() > 1 {
+        sorted = sorted
+            .chunks_mut(2)
+            .map(|pair| match pair {
+                [a, b] => merge(std::mem::take(a), std::mem::take(b), &f),
+                [a] => std::mem::take(a),
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+
+    sorted.pop().unwrap_or_default()
+}
+
+fn merge<T, K: Ord>(a: Vec<T>, b: Vec<T>, f: &impl Fn(&T) -> K) -> Vec<T> {
+    let mut merged = Vec::with_capacity(a.len() + b.len());
+    let (mut a, mut b) = (a.into_iter().peekable(), b.into_iter().peekable());
+
+    while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+        merged.push(if f(y) < f(x) { b.next() } else { a.next() }.unwrap());
+    }
+    merged.extend(a);
+    merged.extend(b);
+    merged
+}
@@ -1,39 +1,72 @@
 use itertools::Itertools;
 use num_rational::Ratio;
-use std::collections::HashSet;
+use std::{iter, sync::Arc};
 
 use crate::{
     errors::HexError,
     hex_math::{get_pattern_segments, Angle, Coord, Direction, Segment},
-    utils::{cloned_push, cloned_union_single, NonZeroSign},
+    utils::NonZeroSign,
 };
 
-use super::{Bounds, MinMax};
+use super::{Bounds, Lookup, MinMax, Occupancy};
 
+/// One segment of a path, linked to the segments drawn before it so that every extension of a path shares them.
+struct Node {
+    segment: Segment,
+    previous: Option<Arc<Node>>,
+}
+
+/// A partial or complete number pattern, along with the value it draws so far.
+///
+/// Paths share their segments with the path they were extended from. Collision checks are bit tests against an
+/// occupancy grid when the search is bounded, and only walk the path on a Bloom filter false positive otherwise.
 #[derive(Clone)]
 pub struct Path {
     sign: NonZeroSign,
     value: Ratio<u64>,
-    segments: Vec<Segment>,
-    segments_set: HashSet<Segment>,
-    points_set: HashSet<Coord>,
+    len: usize,
+    num_points: usize,
+    last: Arc<Node>,
+    occupancy: Occupancy,
     minmax: MinMax,
 }
 
+#[allow(clippy::len_without_is_empty)]
 impl Path {
+    /// The prefix alone, which draws zero.
     pub fn zero(sign: NonZeroSign) -> Self {
+        Self::zero_within(sign, None)
+    }
+
+    /// Like [`Path::zero`], but `bounds` are used to speed up collision checks, and some extensions that can't fit in
+    /// them fail with [`HexError::SegmentOutOfBounds`].
+    pub fn zero_within(sign: NonZeroSign, bounds: Option<Bounds>) -> Self {
+        let direction = match sign {
+            NonZeroSign::Positive => Direction::SouthEast,
+            NonZeroSign::Negative => Direction::NorthEast,
+        };
+        Self::zero_facing(sign, direction, bounds)
+    }
+
+    /// Like [`Path::zero_within`], but the prefix starts facing `direction` instead of the usual way for `sign`, which
+    /// rotates every extension of it.
+    pub fn zero_facing(sign: NonZeroSign, direction: Direction, bounds: Option<Bounds>) -> Self {
         let segments = match sign {
-            NonZeroSign::Positive => get_pattern_segments(Direction::SouthEast, "aqaa"),
-            NonZeroSign::Negative => get_pattern_segments(Direction::NorthEast, "dedd"),
+            NonZeroSign::Positive => get_pattern_segments(direction, "aqaa"),
+            NonZeroSign::Negative => get_pattern_segments(direction, "dedd"),
         }
         .unwrap();
 
+        let last = segments.iter().fold(None, |previous, &segment| Some(Arc::new(Node { segment, previous })));
+        let points: Vec<_> = segments.iter().flat_map(|segment| [segment.root(), segment.end()]).unique().collect();
+
         Self {
             sign,
             value: 0.into(),
-            segments: segments.clone(),
-            segments_set: HashSet::from_iter(segments.clone()),
-            points_set: HashSet::from_iter(segments.iter().flat_map(|segment| [segment.root(), segment.end()])),
+            len: segments.len(),
+            num_points: points.len(),
+            last: last.unwrap(),
+            occupancy: Occupancy::new(bounds, &segments),
             minmax: MinMax::from(&segments),
         }
     }
@@ -46,42 +79,105 @@ impl Path {
         self.minmax.into()
     }
 
+    pub(crate) fn minmax(&self) -> MinMax {
+        self.minmax
+    }
+
+    /// Number of segments, including the prefix.
     pub fn len(&self) -> usize {
-        self.segments.len()
+        self.len
     }
 
     pub fn num_points(&self) -> usize {
-        self.points_set.len()
+        self.num_points
+    }
+
+    fn nodes(&self) -> impl Iterator<Item = &Node> {
+        iter::successors(Some(&*self.last), |node| node.previous.as_deref())
+    }
+
+    fn contains_segment(&self, segment: Segment) -> bool {
+        match self.occupancy.segment(segment) {
+            Lookup::Present => true,
+            Lookup::Maybe => self.nodes().any(|node| node.segment == segment),
+            Lookup::Absent | Lookup::OutOfBounds => false,
+        }
+    }
+
+    fn contains_point(&self, point: Coord) -> bool {
+        match self.occupancy.point(point) {
+            Lookup::Present => true,
+            Lookup::Maybe => self.nodes().any(|node| node.segment.root() == point || node.segment.end() == point),
+            Lookup::Absent | Lookup::OutOfBounds => false,
+        }
     }
 
+    /// Extends the path by one segment, failing if the angle isn't valid in a number, the segment was already drawn, or
+    /// it leaves the bounds the path was started with.
     pub fn with_angle(&self, angle: Angle) -> Result<Self, HexError> {
         let new_value = angle.apply_to(self.value)?;
-        let new_segment = self.segments.last().unwrap().next_segment(angle);
+        let new_segment = self.last.segment.next_segment(angle);
         let new_point = new_segment.end();
 
-        if self.segments_set.contains(&new_segment) {
+        if self.contains_segment(new_segment) {
             return Err(HexError::SegmentAlreadyExists(new_segment));
         }
+        let occupancy = self.occupancy.with(new_segment).ok_or(HexError::SegmentOutOfBounds(new_segment))?;
 
         Ok(Self {
             sign: self.sign,
             value: new_value,
-            segments: cloned_push(&self.segments, new_segment),
-            segments_set: cloned_union_single(&self.segments_set, new_segment),
-            points_set: cloned_union_single(&self.points_set, new_point),
+            len: self.len + 1,
+            num_points: self.num_points + usize::from(!self.contains_point(new_point)),
+            last: Arc::new(Node { segment: new_segment, previous: Some(self.last.clone()) }),
+            occupancy,
             minmax: self.minmax.with_point(new_point),
         })
     }
 
+    /// Every segment in drawing order. This walks the whole path, so it's meant for finished results.
+    pub fn segments(&self) -> Vec<Segment> {
+        let mut segments: Vec<_> = self.nodes().map(|node| node.segment).collect();
+        segments.reverse();
+        segments
+    }
+
     pub fn starting_direction(&self) -> Direction {
-        self.segments[0].direction()
+        self.segments()[0].direction()
     }
 
-    pub fn pattern(&self) -> String {
-        self.segments.iter().tuple_windows().map(|(a, b)| char::from(b.direction().angle_from(a.direction()))).collect()
+    /// Whether both paths are the same clone, rather than just drawing the same pattern.
+    pub(crate) fn is_same(&self, other: &Path) -> bool {
+        Arc::ptr_eq(&self.last, &other.last)
     }
 
-    pub fn should_replace(&self, other: &Option<Path>) -> bool {
-        other.as_ref().filter(|path| !self.bounds().is_better_than(path.bounds())).is_none()
+    /// The last segment drawn, which every extension continues from.
+    pub fn end_segment(&self) -> Segment {
+        self.last.segment
+    }
+
+    /// Whether any way of extending `other` would also work on this path, giving the same value with at most the same
+    /// length, points and bounds.
+    pub(crate) fn dominates(&self, other: &Path) -> bool {
+        let (end, other_end) = (self.end_segment(), other.end_segment());
+
+        self.value == other.value
+            && end.end() == other_end.end()
+            && end.direction() == other_end.direction()
+            && self.len() <= other.len()
+            && self.minmax.within(&other.minmax)
+            && self
+                .occupancy
+                .is_subset_of(&other.occupancy)
+                .unwrap_or_else(|| self.nodes().all(|node| other.contains_segment(node.segment)))
+    }
+
+    /// The angle string, eg. `aqaaeaqaa`. Like [`Path::segments`], this walks the whole path.
+    pub fn pattern(&self) -> String {
+        self.segments()
+            .iter()
+            .tuple_windows()
+            .map(|(a, b)| char::from(b.direction().angle_from(a.direction())))
+            .collect()
     }
 }
@@ -0,0 +1,56 @@
+use std::str::FromStr;
+
+use strum::{Display, EnumIter, EnumString};
+
+use crate::errors::HexError;
+
+/// Something beam search ranks paths by, best first. Parses from and displays as eg. `log-distance`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumIter, EnumString)]
+#[strum(serialize_all = "kebab-case")]
+pub enum RankKey {
+    /// Fewest segments
+    Length,
+    /// Closest value to the target
+    Distance,
+    /// Closest value to the target by `|ln(1 + value) - ln(1 + target)|`, which is zero-safe and, away from zero, close
+    /// to comparing by ratio rather than difference, so a path at half the target ranks about the same as one at double
+    /// it
+    LogDistance,
+    NumPoints,
+    QuasiArea,
+    LargestDimension,
+    /// A random order, to keep some variety in the beam
+    Random,
+}
+
+/// Which paths beam search keeps after each step: each key in turn takes the best of the paths that are left, up to its
+/// quota. Parses from eg. `length=10,distance,random=5`, where a key without a quota keeps
+/// [`GeneratorConfig::carryover`](super::GeneratorConfig::carryover) paths.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ranking(pub Vec<(RankKey, Option<usize>)>);
+
+impl Default for Ranking {
+    /// Shortest, then closest to the target, then fewest points.
+    fn default() -> Self {
+        Self(vec![(RankKey::Length, None), (RankKey::Distance, None), (RankKey::NumPoints, None)])
+    }
+}
+
+impl FromStr for Ranking {
+    type Err = HexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || HexError::InvalidRanking(s.to_string());
+
+        s.split(',')
+            .map(|term| {
+                let (key, quota) = match term.split_once('=') {
+                    Some((key, quota)) => (key, Some(quota.trim().parse().map_err(|_| invalid())?)),
+                    None => (term, None),
+                };
+                Ok((key.trim().parse().map_err(|_| invalid())?, quota))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
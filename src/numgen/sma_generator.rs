@@ -0,0 +1,334 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashSet},
+    sync::{
+        atomic::{AtomicU8, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use num_rational::Ratio;
+use num_traits::Zero;
+use strum::IntoEnumIterator;
+
+use crate::{hex_math::Angle, traits::UnsignedAbsRatio, utils::NonZeroSign};
+
+use super::{
+    Budget, DistanceTable, GeneratorConfig, Improvement, Optimality, Path, PathGenerator, SearchOutcome, Solutions,
+    StopReason,
+};
+
+/// One bit per angle, for sets of children.
+fn angle_bit(angle: Angle) -> u8 {
+    1 << angle as u8
+}
+
+/// A path in memory. Expanded nodes aren't queued, and only stay in memory while something below them does.
+struct Node {
+    path: Path,
+    parent: Option<Arc<Node>>,
+    /// The angle that extends the parent's path into this one
+    angle: Option<Angle>,
+    /// Number of nodes from the root down to this one, which all have to stay in memory along with it
+    depth: usize,
+    /// Lower bound on the length of any finished path through this one
+    f: usize,
+    /// Angles of the children to generate when this is expanded
+    pending: u8,
+    /// Lowest `f` among children that were forgotten to make room, or `usize::MAX` if none were
+    forgotten_f: AtomicUsize,
+    /// Angles of the children that were forgotten
+    forgotten: AtomicU8,
+}
+
+impl Node {
+    fn root(path: Path, f: usize) -> Self {
+        Self::new(path, None, None, 1, f, u8::MAX)
+    }
+
+    fn child(parent: &Arc<Node>, angle: Angle, path: Path, f: usize) -> Self {
+        Self::new(path, Some(parent.clone()), Some(angle), parent.depth + 1, f, u8::MAX)
+    }
+
+    fn new(path: Path, parent: Option<Arc<Node>>, angle: Option<Angle>, depth: usize, f: usize, pending: u8) -> Self {
+        Self {
+            path,
+            parent,
+            angle,
+            depth,
+            f,
+            pending,
+            forgotten_f: AtomicUsize::new(usize::MAX),
+            forgotten: AtomicU8::new(0),
+        }
+    }
+
+    fn steps_left(&self) -> usize {
+        self.f - self.path.len()
+    }
+}
+
+/// Queued nodes, taken out deepest first among the lowest `f` and forgotten shallowest first among the highest, so the
+/// path being followed down isn't forgotten to make room for its own children.
+struct Frontier {
+    nodes: BTreeMap<(usize, Reverse<usize>), Vec<Node>>,
+    len: usize,
+}
+
+impl Frontier {
+    fn new() -> Self {
+        Self { nodes: BTreeMap::new(), len: 0 }
+    }
+
+    fn push(&mut self, node: Node) {
+        self.nodes.entry((node.f, Reverse(node.depth))).or_default().push(node);
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<Node> {
+        let mut entry = self.nodes.first_entry()?;
+        let node = entry.get_mut().pop();
+        if entry.get().is_empty() {
+            entry.remove();
+        }
+        self.len -= 1;
+        node
+    }
+
+    fn pop_worst(&mut self) -> Option<Node> {
+        let mut entry = self.nodes.last_entry()?;
+        let node = entry.get_mut().pop();
+        if entry.get().is_empty() {
+            entry.remove();
+        }
+        self.len -= 1;
+        node
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Node> {
+        self.nodes.values().flatten()
+    }
+}
+
+/// Memory-bounded A* (SMA*), which forgets the worst queued paths once more than [`GeneratorConfig::max_nodes`] paths
+/// are queued or waiting on their children.
+///
+/// A forgotten path's parent remembers the lowest bound among its forgotten children, and once nothing else below it is
+/// left it's queued again with that bound, generating only the forgotten children. So no part of the search space is
+/// lost for good, unless a path is too long to fit in memory along with every path it extends, in which case the search
+/// stops with [`StopReason::MaxNodes`]. Transpositions aren't tracked, since that would keep every path in memory.
+pub struct SmaStarPathGenerator {
+    target: Ratio<u64>,
+    config: GeneratorConfig,
+    distances: DistanceTable,
+    solutions: Solutions,
+    frontier: Frontier,
+    /// Number of nodes in memory, whether queued or expanded
+    nodes: usize,
+    /// Lowest objective bound among paths that were too long to keep in memory
+    cut_off_bound: Option<u64>,
+}
+
+impl SmaStarPathGenerator {
+    pub fn new(config: GeneratorConfig) -> Self {
+        let mut gen = Self {
+            target: config.target.unsigned_abs(),
+            distances: DistanceTable::new(&config),
+            solutions: Solutions::new(&config),
+            frontier: Frontier::new(),
+            nodes: 1,
+            cut_off_bound: None,
+            config,
+        };
+        let root = Path::zero_within(NonZeroSign::from(gen.config.target), gen.config.bounds);
+        let f = root.len() + gen.distances.steps_left(root.value());
+        gen.frontier.push(Node::root(root, f));
+        gen
+    }
+
+    fn max_nodes(&self) -> usize {
+        self.config.max_nodes.max(1)
+    }
+
+    fn could_improve(&self, node: &Node) -> bool {
+        self.solutions.could_improve(&node.path, node.steps_left())
+    }
+
+    /// Expands `node`, returning any new solutions that were kept.
+    fn expand(&mut self, node: Node) -> Vec<Path> {
+        let node = Arc::new(node);
+        let mut kept = Vec::new();
+
+        for angle in Angle::iter().filter(|&angle| node.pending & angle_bit(angle) != 0) {
+            let Ok(new_path) = node.path.with_angle(angle) else {
+                continue;
+            };
+            // a parent's bound can be higher than its children's if they were forgotten before
+            let f = (new_path.len() + self.distances.steps_left(new_path.value())).max(node.f);
+            let child = Node::child(&node, angle, new_path, f);
+            if !self.config.allows(&child.path) || !self.could_improve(&child) {
+                continue;
+            }
+
+            if child.path.value() == self.target && self.solutions.offer(&child.path) {
+                kept.push(child.path.clone());
+            }
+            if child.depth > self.max_nodes() {
+                let bound = self.config.objective.lower_bound(&child.path, child.steps_left());
+                self.cut_off_bound = Some(self.cut_off_bound.map_or(bound, |cut_off| cut_off.min(bound)));
+                continue;
+            }
+            self.frontier.push(child);
+            self.nodes += 1;
+        }
+
+        self.release(node);
+        kept
+    }
+
+    /// Takes `node` out of memory. If it was `evicted` rather than finished with, its parent remembers it.
+    fn drop_node(&mut self, node: Node, evicted: bool) {
+        self.nodes -= 1;
+        let Some(parent) = node.parent else {
+            return;
+        };
+        if let (true, Some(angle)) = (evicted, node.angle) {
+            parent.forgotten_f.fetch_min(node.f, Ordering::Relaxed);
+            parent.forgotten.fetch_or(angle_bit(angle), Ordering::Relaxed);
+        }
+        self.release(parent);
+    }
+
+    /// Drops a reference to an expanded node. If nothing below it is left, it's queued again to generate its forgotten
+    /// children, with its bound backed up to the lowest of theirs, or dropped too if none were forgotten.
+    fn release(&mut self, node: Arc<Node>) {
+        let Ok(node) = Arc::try_unwrap(node) else {
+            return;
+        };
+
+        match node.forgotten.load(Ordering::Relaxed) {
+            0 => self.drop_node(node, false),
+            forgotten => {
+                let f = node.f.max(node.forgotten_f.load(Ordering::Relaxed));
+                self.frontier.push(Node::new(node.path, node.parent, node.angle, node.depth, f, forgotten));
+            }
+        }
+    }
+
+    fn evict(&mut self) {
+        while self.nodes > self.max_nodes() {
+            let Some(worst) = self.frontier.pop_worst() else {
+                break;
+            };
+            // no need to remember paths that were already overtaken
+            let evicted = self.could_improve(&worst);
+            self.drop_node(worst, evicted);
+        }
+    }
+
+    /// The lowest objective bound among queued paths, and paths with forgotten children that are still in memory.
+    fn unexplored_bound(&self) -> Option<u64> {
+        let mut seen = HashSet::new();
+        let mut bounds = Vec::new();
+
+        for node in self.frontier.iter().filter(|node| self.could_improve(node)) {
+            bounds.push(self.config.objective.lower_bound(&node.path, node.steps_left()));
+
+            let mut parent = node.parent.as_ref();
+            while let Some(node) = parent.filter(|&node| seen.insert(Arc::as_ptr(node))) {
+                let forgotten_f = node.forgotten_f.load(Ordering::Relaxed);
+                if forgotten_f != usize::MAX {
+                    let steps_left = node.f.max(forgotten_f) - node.path.len();
+                    bounds.push(self.config.objective.lower_bound(&node.path, steps_left));
+                }
+                parent = node.parent.as_ref();
+            }
+        }
+
+        bounds.into_iter().chain(self.cut_off_bound).min()
+    }
+}
+
+impl PathGenerator for SmaStarPathGenerator {
+    fn run_with(&mut self, on_improvement: &mut dyn FnMut(&Improvement)) -> SearchOutcome {
+        let mut budget = Budget::start(self.config.limits.clone());
+
+        if self.target.is_zero() {
+            let path = self.frontier.pop().unwrap().path;
+            return SearchOutcome::zero(path, &self.config, &budget, on_improvement);
+        }
+
+        let stop_reason = loop {
+            if let Some(reason) = budget.exhausted(self.frontier.len()) {
+                break Some(reason);
+            }
+            let Some(node) = self.frontier.pop() else {
+                // anything cut off for being too long may still have led somewhere better
+                break self.cut_off_bound.map(|_| StopReason::MaxNodes);
+            };
+            // the frontier isn't filtered when solutions are found, so this may have been overtaken since it was queued
+            if !self.could_improve(&node) {
+                self.drop_node(node, false);
+                continue;
+            }
+            budget.expand(1);
+
+            for path in self.expand(node) {
+                on_improvement(&Improvement::new(&path, budget.elapsed()));
+            }
+            self.evict();
+        };
+
+        SearchOutcome {
+            solutions: self.solutions.paths().to_vec(),
+            optimality: if stop_reason.is_none() {
+                Optimality::exhaustive(&self.config)
+            } else {
+                Optimality::Heuristic
+            },
+            lower_bound: self.solutions.lower_bound(self.unexplored_bound()),
+            seed: None,
+            stop_reason,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::numgen::{AStarPathGenerator, SearchLimits};
+
+    fn config(target: i64, max_nodes: usize) -> GeneratorConfig {
+        // a time limit so that a search that never finishes fails instead of hanging
+        let limits = SearchLimits { time: Some(Duration::from_secs(30)), ..SearchLimits::default() };
+        GeneratorConfig { max_nodes, limits, ..GeneratorConfig::new(target.into()) }
+    }
+
+    #[test]
+    fn finishes_with_few_nodes() {
+        for (target, max_nodes) in [(27, 10), (137, 20), (100, 15), (300, 30)] {
+            let config = config(target, max_nodes);
+            let expected = AStarPathGenerator::new(config.clone()).run();
+            let outcome = SmaStarPathGenerator::new(config.clone()).run();
+
+            assert_eq!(outcome.stop_reason, None, "{target} with {max_nodes} nodes");
+            let cost = |outcome: &SearchOutcome| outcome.best().map(|path| config.objective.cost(path));
+            assert_eq!(cost(&outcome), cost(&expected), "{target} with {max_nodes} nodes");
+        }
+    }
+
+    #[test]
+    fn stops_when_paths_dont_fit_in_memory() {
+        for (target, max_nodes) in [(27, 4), (100, 3)] {
+            let outcome = SmaStarPathGenerator::new(config(target, max_nodes)).run();
+            assert_eq!(outcome.stop_reason, Some(StopReason::MaxNodes), "{target} with {max_nodes} nodes");
+            assert_eq!(outcome.optimality, Optimality::Heuristic);
+        }
+    }
+}
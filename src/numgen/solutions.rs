@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use strum::IntoEnumIterator;
+
+use super::{GeneratorConfig, Metric, Objective, Path};
+
+/// Which solutions a search keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Collect {
+    /// Only the best path under the objective
+    #[default]
+    Best,
+    /// Every path that isn't dominated in all of the [`Metric`]s by another path
+    ParetoFront,
+    /// The best `k` distinct patterns under the objective, counting rotations of the same shape once. Dominated paths
+    /// aren't skipped in this mode, since they can still draw distinct patterns
+    Top(usize),
+}
+
+/// Lower bounds on every metric of any finished path that `path` could be extended into.
+fn metrics(path: &Path, steps_left: usize) -> Vec<u64> {
+    Metric::iter().map(|metric| metric.lower_bound(path, steps_left)).collect()
+}
+
+/// Whether `a` is at least as good as `b` in every metric.
+fn weakly_dominates(a: &[u64], b: &[u64]) -> bool {
+    a.iter().zip(b).all(|(a, b)| a <= b)
+}
+
+/// The solutions kept so far by a search, sorted best first under the objective.
+pub(crate) struct Solutions {
+    collect: Collect,
+    objective: Arc<dyn Objective>,
+    paths: Vec<Path>,
+    /// Cost that a new path has to beat to be kept, if there is one yet (best or top `k` only)
+    bar: Option<u64>,
+    /// Paths have to beat the bar by this factor to be kept (best or top `k` only)
+    epsilon: f64,
+}
+
+impl Solutions {
+    pub fn new(config: &GeneratorConfig) -> Self {
+        Self {
+            collect: config.collect,
+            objective: config.objective.clone(),
+            paths: Vec::new(),
+            bar: None,
+            epsilon: config.epsilon.max(1.0),
+        }
+    }
+
+    pub fn best(&self) -> Option<&Path> {
+        self.paths.first()
+    }
+
+    /// Whether `path` or anything it could be extended into might still be kept, given that it needs at least
+    /// `steps_left` more segments to reach the target.
+    ///
+    /// This relies on the objective and all metrics never decreasing as a path gets longer.
+    pub fn could_improve(&self, path: &Path, steps_left: usize) -> bool {
+        match self.collect {
+            Collect::Best | Collect::Top(_) => self
+                .bar
+                .is_none_or(|bar| (self.objective.lower_bound(path, steps_left) as f64) * self.epsilon < bar as f64),
+            Collect::ParetoFront => {
+                let path_metrics = metrics(path, steps_left);
+                !self.paths.iter().any(|other| weakly_dominates(&metrics(other, 0), &path_metrics))
+            }
+        }
+    }
+
+    /// Offers a finished path, returning true if it was kept.
+    pub fn offer(&mut self, path: &Path) -> bool {
+        if !self.could_improve(path, 0) {
+            return false;
+        }
+
+        match self.collect {
+            Collect::Best => self.paths = vec![path.clone()],
+            Collect::ParetoFront => {
+                let path_metrics = metrics(path, 0);
+                self.paths.retain(|other| !weakly_dominates(&path_metrics, &metrics(other, 0)));
+
+                self.insert_sorted(path);
+            }
+            Collect::Top(k) => {
+                // the angle string doesn't depend on the starting direction, so rotations share a pattern
+                let pattern = path.pattern();
+                if self.paths.iter().any(|other| other.pattern() == pattern) {
+                    return false;
+                }
+
+                self.insert_sorted(path);
+                self.paths.truncate(k);
+            }
+        }
+
+        self.bar = match self.collect {
+            Collect::Best => self.best().map(|best| self.objective.cost(best)),
+            Collect::Top(k) if self.paths.len() == k => self.paths.last().map(|last| self.objective.cost(last)),
+            _ => None,
+        };
+        true
+    }
+
+    /// Inserts after any paths with the same cost, so earlier finds win ties.
+    fn insert_sorted(&mut self, path: &Path) {
+        let cost = self.objective.cost(path);
+        let index = self.paths.partition_point(|other| self.objective.cost(other) <= cost);
+        self.paths.insert(index, path.clone());
+    }
+
+    /// The lowest cost a finished path could have, given the lowest bound among paths that weren't explored. `None` if
+    /// nothing was found and there's nothing left to explore.
+    pub fn lower_bound(&self, unexplored: Option<u64>) -> Option<u64> {
+        let epsilon = if self.collect == Collect::ParetoFront { 1.0 } else { self.epsilon };
+        // anything skipped couldn't beat the best path by more than epsilon
+        let skipped = self.best().map(|best| (self.objective.cost(best) as f64 / epsilon).ceil() as u64);
+        skipped.into_iter().chain(unexplored).min()
+    }
+
+    pub fn paths(&self) -> &[Path] {
+        &self.paths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::numgen::{AStarPathGenerator, PathGenerator};
+
+    #[test]
+    fn top_k_is_distinct_and_sorted() {
+        for target in [27, 100, 137] {
+            let best = AStarPathGenerator::new(GeneratorConfig::new(target.into())).run();
+            let config = GeneratorConfig { collect: Collect::Top(5), ..GeneratorConfig::new(target.into()) };
+            let outcome = AStarPathGenerator::new(config.clone()).run();
+            assert_eq!(outcome.solutions.len(), 5, "{target}");
+
+            let patterns: HashSet<_> = outcome.solutions.iter().map(Path::pattern).collect();
+            assert_eq!(patterns.len(), 5, "{target}");
+
+            let costs: Vec<_> = outcome.solutions.iter().map(|path| config.objective.cost(path)).collect();
+            assert!(costs.is_sorted(), "{target}: {costs:?}");
+            assert_eq!(costs[0], config.objective.cost(best.best().unwrap()), "{target}");
+        }
+    }
+}
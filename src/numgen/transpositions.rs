@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use num_rational::Ratio;
+
+use super::{Collect, GeneratorConfig, Path};
+use crate::hex_math::{Coord, Direction};
+
+/// Remembers the paths seen so far by their value and where and which way they end, so that paths dominated by an
+/// earlier one can be skipped. Only enabled for objectives where that can't skip a better pattern.
+pub(crate) struct TranspositionTable {
+    enabled: bool,
+    paths: HashMap<(Ratio<u64>, Coord, Direction), Vec<Path>>,
+}
+
+impl TranspositionTable {
+    pub fn new(config: &GeneratorConfig) -> Self {
+        // a dominated path can still draw a distinct pattern, which top-k collection needs
+        let enabled = config.transpositions
+            && config.objective.supports_dominance()
+            && !matches!(config.collect, Collect::Top(_));
+        Self { enabled, paths: HashMap::new() }
+    }
+
+    fn key(path: &Path) -> (Ratio<u64>, Coord, Direction) {
+        let end = path.end_segment();
+        (path.value(), end.end(), end.direction())
+    }
+
+    /// Whether a path recorded earlier dominates `path`.
+    pub fn is_dominated(&self, path: &Path) -> bool {
+        self.enabled
+            && self.paths.get(&Self::key(path)).is_some_and(|paths| paths.iter().any(|other| other.dominates(path)))
+    }
+
+    /// Records `path`, returning false if a path seen earlier dominates it.
+    pub fn insert(&mut self, path: &Path) -> bool {
+        if !self.enabled {
+            return true;
+        }
+
+        let paths = self.paths.entry(Self::key(path)).or_default();
+        if paths.iter().any(|other| other.dominates(path)) {
+            return false;
+        }
+
+        paths.retain(|other| !path.dominates(other));
+        paths.push(path.clone());
+        true
+    }
+
+    /// Forgets every path but `paths`, which were recorded already, so that memory doesn't grow with every path seen.
+    pub fn keep_only(&mut self, paths: &[Path]) {
+        if !self.enabled {
+            return;
+        }
+
+        self.paths.clear();
+        for path in paths {
+            self.paths.entry(Self::key(path)).or_default().push(path.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{hex_math::Angle, utils::NonZeroSign};
+
+    #[test]
+    fn only_enabled_for_objectives_that_support_dominance() {
+        let config = GeneratorConfig::new(10.into());
+        assert!(TranspositionTable::new(&config).enabled);
+
+        let custom = GeneratorConfig { objective: Arc::new(|path: &Path| path.len() as u64), ..config.clone() };
+        assert!(!TranspositionTable::new(&custom).enabled);
+
+        let top = GeneratorConfig { collect: Collect::Top(3), ..config };
+        assert!(!TranspositionTable::new(&top).enabled);
+    }
+
+    #[test]
+    fn forgets_paths_not_kept() {
+        let mut table = TranspositionTable::new(&GeneratorConfig::new(10.into()));
+        let path = Path::zero(NonZeroSign::Positive).with_angle(Angle::Forward).unwrap();
+        let other = path.with_angle(Angle::Right).unwrap();
+
+        assert!(table.insert(&path) && table.insert(&other));
+        assert!(table.is_dominated(&path));
+
+        table.keep_only(std::slice::from_ref(&other));
+        assert!(!table.is_dominated(&path));
+        assert!(table.is_dominated(&other));
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::HashSet;
+
+use crate::{
+    errors::HexError,
+    hex_math::{get_pattern_segments, Angle, Direction, Segment},
+};
+
+use super::{Bounds, MinMax};
+
+/// Problems found by [`validate_pattern`].
+#[derive(Debug, Clone)]
+pub struct PatternReport {
+    /// Segments that were already drawn earlier in the pattern, in drawing order
+    pub overlapping_segments: Vec<Segment>,
+    /// Indices into the pattern string of any `s` (back) angles
+    pub back_angles: Vec<usize>,
+    pub bounds: Bounds,
+    pub fits_in_bounds: bool,
+}
+
+impl PatternReport {
+    pub fn is_valid(&self) -> bool {
+        self.overlapping_segments.is_empty() && self.back_angles.is_empty() && self.fits_in_bounds
+    }
+}
+
+/// Checks a finished pattern for reused segments and back angles, and whether it fits in `max_bounds`.
+pub fn validate_pattern(direction: Direction, pattern: &str, max_bounds: Bounds) -> Result<PatternReport, HexError> {
+    let segments = get_pattern_segments(direction, pattern)?;
+
+    let mut seen = HashSet::new();
+    let overlapping_segments = segments.iter().filter(|&&segment| !seen.insert(segment)).copied().collect();
+
+    let back_angles = pattern
+        .chars()
+        .enumerate()
+        .filter(|&(_, c)| matches!(Angle::try_from(c), Ok(Angle::Back)))
+        .map(|(i, _)| i)
+        .collect();
+
+    let bounds = Bounds::from(MinMax::from(&segments));
+
+    Ok(PatternReport { overlapping_segments, back_angles, bounds, fits_in_bounds: bounds.fits_in(max_bounds) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode_number_pattern, generate_number_pattern, Algorithm, GeneratorConfig};
+
+    fn validate(pattern: &str) -> PatternReport {
+        validate_pattern(Direction::SouthEast, pattern, Bounds::from(8)).unwrap()
+    }
+
+    #[test]
+    fn accepts_generated_patterns() {
+        for target in [1, 10, 27, 137, 1234, -64] {
+            for algorithm in [Algorithm::Beam, Algorithm::AStar] {
+                let config = GeneratorConfig::new(target.into());
+                let number = generate_number_pattern(config, algorithm).unwrap();
+                let direction = number.direction.parse().unwrap();
+
+                let report = validate_pattern(direction, &number.pattern, Bounds::from(8)).unwrap();
+                assert!(report.is_valid(), "{number}: {report:?}");
+                assert_eq!(decode_number_pattern(direction, &number.pattern).unwrap(), target.into(), "{number}");
+            }
+        }
+    }
+
+    #[test]
+    fn finds_overlapping_segments() {
+        // turning left every time goes round a hexagon and back onto an earlier segment
+        let report = validate("aqaawqqqqq");
+        assert!(!report.is_valid());
+        assert_eq!(report.overlapping_segments.len(), 1);
+        assert!(report.back_angles.is_empty());
+    }
+
+    #[test]
+    fn finds_back_angles() {
+        let report = validate("aqaawsw");
+        assert!(!report.is_valid());
+        assert_eq!(report.back_angles, [5]);
+    }
+
+    #[test]
+    fn checks_bounds() {
+        let report = validate(&format!("aqaa{}", "w".repeat(10)));
+        assert!(!report.is_valid());
+        assert!(!report.fits_in_bounds);
+        assert!(report.overlapping_segments.is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_character() {
+        assert!(matches!(
+            validate_pattern(Direction::SouthEast, "aqaax", Bounds::from(8)),
+            Err(HexError::InvalidChar('x'))
+        ));
+    }
+}
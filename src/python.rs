@@ -0,0 +1,375 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use num_rational::Ratio;
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+    types::PyDict,
+};
+
+use crate::{
+    decode_number_pattern, generate_number_pattern, generate_number_pattern_astar,
+    generate_number_pattern_astar_bounded, generate_number_patterns, validate_pattern, Algorithm, Bounds, BoundsMetric,
+    Collect, Feasibility, FeasibilityCheck, GeneratedNumber, GeneratorConfig, HexError, Improvements,
+    MinimumBoundsSearch, PatternReport, Ranking, Weighted,
+};
+
+#[derive(FromPyObject)]
+pub enum PyRatio {
+    #[pyo3(annotation = "int")]
+    Int(i64),
+    #[pyo3(annotation = "tuple[int, int]")]
+    Tuple(i64, i64),
+}
+
+impl From<PyRatio> for Ratio<i64> {
+    fn from(value: PyRatio) -> Self {
+        match value {
+            PyRatio::Int(n) => n.into(),
+            PyRatio::Tuple(numer, denom) => Ratio::new(numer, denom),
+        }
+    }
+}
+
+#[pymethods]
+impl GeneratedNumber {
+    #[getter]
+    fn direction(&self) -> String {
+        self.direction.clone()
+    }
+
+    #[getter]
+    fn pattern(&self) -> String {
+        self.pattern.clone()
+    }
+
+    #[getter]
+    fn quasi_area(&self) -> u32 {
+        self.quasi_area
+    }
+
+    #[getter]
+    fn largest_dimension(&self) -> u32 {
+        self.largest_dimension
+    }
+
+    #[getter]
+    fn num_points(&self) -> usize {
+        self.num_points
+    }
+
+    #[getter]
+    fn stopped_early(&self) -> bool {
+        self.stopped_early
+    }
+
+    #[getter]
+    fn optimality(&self) -> String {
+        self.optimality.to_string()
+    }
+
+    #[getter]
+    fn lower_bound(&self) -> Option<u64> {
+        self.lower_bound
+    }
+
+    #[getter]
+    fn within_factor(&self) -> Option<f64> {
+        self.within_factor
+    }
+
+    #[getter]
+    fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    fn __str__(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[pyclass(name = "PatternReport")]
+pub struct PyPatternReport {
+    /// `(q, r, direction)` of each segment drawn more than once
+    #[pyo3(get)]
+    pub overlapping_segments: Vec<(i32, i32, String)>,
+    #[pyo3(get)]
+    pub back_angles: Vec<usize>,
+    /// `(q, r, s)`
+    #[pyo3(get)]
+    pub bounds: (u32, u32, u32),
+    #[pyo3(get)]
+    pub fits_in_bounds: bool,
+    #[pyo3(get)]
+    pub is_valid: bool,
+}
+
+impl From<PatternReport> for PyPatternReport {
+    fn from(report: PatternReport) -> Self {
+        let is_valid = report.is_valid();
+        Self {
+            overlapping_segments: report
+                .overlapping_segments
+                .iter()
+                .map(|segment| (segment.root().q(), segment.root().r(), segment.direction().to_string()))
+                .collect(),
+            back_angles: report.back_angles,
+            bounds: (report.bounds.q(), report.bounds.r(), report.bounds.s()),
+            fits_in_bounds: report.fits_in_bounds,
+            is_valid,
+        }
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "generate_number_pattern_beam")]
+#[allow(clippy::too_many_arguments)]
+fn generate_number_pattern_beam_py(
+    target: PyRatio,
+    q_size: Option<u32>,
+    r_size: Option<u32>,
+    s_size: Option<u32>,
+    carryover: Option<usize>,
+    trim_larger: Option<bool>,
+    allow_fractions: Option<bool>,
+    ranking: Option<&str>,
+    stochastic: Option<bool>,
+    restarts: Option<usize>,
+    seed: Option<u64>,
+) -> PyResult<Option<GeneratedNumber>> {
+    let ranking: Option<Ranking> =
+        ranking.map(str::parse).transpose().map_err(|err: HexError| PyValueError::new_err(err.to_string()))?;
+    let config = GeneratorConfig {
+        bounds: Some(Bounds::new(q_size.unwrap_or(8), r_size.unwrap_or(8), s_size.unwrap_or(8))),
+        carryover: carryover.unwrap_or(25),
+        ranking: ranking.unwrap_or_default(),
+        stochastic: stochastic.unwrap_or(false),
+        restarts: restarts.unwrap_or(0),
+        seed: seed.unwrap_or(0),
+        trim_larger: trim_larger.unwrap_or(true),
+        allow_fractions: allow_fractions.unwrap_or(false),
+        ..GeneratorConfig::new(target.into())
+    };
+    Ok(generate_number_pattern(config, Algorithm::Beam))
+}
+
+#[pyfunction]
+#[pyo3(name = "generate_number_pattern_astar")]
+fn generate_number_pattern_astar_py(
+    target: PyRatio,
+    trim_larger: Option<bool>,
+    allow_fractions: Option<bool>,
+    q_size: Option<u32>,
+    r_size: Option<u32>,
+    s_size: Option<u32>,
+) -> Option<GeneratedNumber> {
+    let (trim_larger, allow_fractions) = (trim_larger.unwrap_or(true), allow_fractions.unwrap_or(false));
+    match sized_bounds(q_size, r_size, s_size, false) {
+        Some(bounds) => generate_number_pattern_astar_bounded(target.into(), bounds, trim_larger, allow_fractions),
+        None => generate_number_pattern_astar(target.into(), trim_larger, allow_fractions),
+    }
+}
+
+/// Bounds from any sizes that were given, with the rest defaulting to 8. If none were given, only bounded searches get
+/// the default bounds.
+fn sized_bounds(q_size: Option<u32>, r_size: Option<u32>, s_size: Option<u32>, bounded: bool) -> Option<Bounds> {
+    (bounded || q_size.is_some() || r_size.is_some() || s_size.is_some())
+        .then(|| Bounds::new(q_size.unwrap_or(8), r_size.unwrap_or(8), s_size.unwrap_or(8)))
+}
+
+/// Builds a config from the keyword arguments accepted by `generate_number_pattern` and `stream_number_patterns`.
+fn config_from_kwargs(target: PyRatio, algorithm: Algorithm, kwargs: Option<&PyDict>) -> PyResult<GeneratorConfig> {
+    let mut config = GeneratorConfig::new(target.into());
+    let (mut q_size, mut r_size, mut s_size) = (None, None, None);
+    let (mut pareto, mut count) = (false, 1);
+
+    for (key, value) in kwargs.into_iter().flatten() {
+        match key.extract()? {
+            "q_size" => q_size = value.extract()?,
+            "r_size" => r_size = value.extract()?,
+            "s_size" => s_size = value.extract()?,
+            "carryover" => config.carryover = value.extract()?,
+            "max_length" => config.max_length = value.extract()?,
+            "backward_depth" => config.backward_depth = value.extract()?,
+            "max_nodes" => config.max_nodes = value.extract()?,
+            "trim_larger" => config.trim_larger = value.extract()?,
+            "allow_fractions" => config.allow_fractions = value.extract()?,
+            "epsilon" => config.epsilon = value.extract()?,
+            "transpositions" => config.transpositions = value.extract()?,
+            "threads" => config.threads = value.extract()?,
+            "timeout" => config.limits.time = value.extract::<Option<f64>>()?.map(Duration::from_secs_f64),
+            "max_expanded" => config.limits.max_expanded = value.extract()?,
+            "max_frontier" => config.limits.max_frontier = value.extract()?,
+            "pareto" => pareto = value.extract()?,
+            "count" => count = value.extract()?,
+            "stochastic" => config.stochastic = value.extract()?,
+            "restarts" => config.restarts = value.extract()?,
+            "seed" => config.seed = value.extract()?,
+            "ranking" => {
+                let ranking: Ranking =
+                    value.extract::<&str>()?.parse().map_err(|err: HexError| PyValueError::new_err(err.to_string()))?;
+                config.ranking = ranking;
+            }
+            "objective" => {
+                let objective: Weighted =
+                    value.extract::<&str>()?.parse().map_err(|err: HexError| PyValueError::new_err(err.to_string()))?;
+                config.objective = Arc::new(objective);
+            }
+            key => return Err(PyTypeError::new_err(format!("unexpected keyword argument `{key}`"))),
+        }
+    }
+
+    config.bounds = sized_bounds(q_size, r_size, s_size, algorithm == Algorithm::Beam);
+    config.collect = match (pareto, count) {
+        (true, 1) => Collect::ParetoFront,
+        (true, _) => return Err(PyValueError::new_err("`pareto` and `count` can't be used together")),
+        (false, 0) => return Err(PyValueError::new_err("`count` must be at least 1")),
+        (false, 1) => Collect::Best,
+        (false, count) => Collect::Top(count),
+    };
+    Ok(config)
+}
+
+fn parse_algorithm(algorithm: Option<&str>) -> PyResult<Algorithm> {
+    algorithm.unwrap_or("beam").parse().map_err(|_| PyValueError::new_err("unknown algorithm"))
+}
+
+#[pyfunction(kwargs = "**")]
+#[pyo3(name = "generate_number_pattern")]
+fn generate_number_pattern_py(
+    target: PyRatio,
+    algorithm: Option<&str>,
+    kwargs: Option<&PyDict>,
+) -> PyResult<Option<GeneratedNumber>> {
+    let algorithm = parse_algorithm(algorithm)?;
+    Ok(generate_number_pattern(config_from_kwargs(target, algorithm, kwargs)?, algorithm))
+}
+
+#[pyfunction(kwargs = "**")]
+#[pyo3(name = "generate_number_patterns")]
+fn generate_number_patterns_py(
+    target: PyRatio,
+    algorithm: Option<&str>,
+    kwargs: Option<&PyDict>,
+) -> PyResult<Vec<GeneratedNumber>> {
+    let algorithm = parse_algorithm(algorithm)?;
+    Ok(generate_number_patterns(config_from_kwargs(target, algorithm, kwargs)?, algorithm))
+}
+
+/// A pattern, along with the bounds and carryover of the beam search that found it.
+type FoundInBounds = (GeneratedNumber, (u32, u32, u32), usize);
+
+#[pyfunction(kwargs = "**")]
+#[pyo3(name = "find_minimum_bounds")]
+fn find_minimum_bounds_py(
+    target: PyRatio,
+    by: Option<&str>,
+    max_carryover: Option<usize>,
+    kwargs: Option<&PyDict>,
+) -> PyResult<Option<FoundInBounds>> {
+    let by: BoundsMetric = by.unwrap_or("quasi-area").parse().map_err(|_| PyValueError::new_err("unknown metric"))?;
+    let config = config_from_kwargs(target, Algorithm::Beam, kwargs)?;
+    let search = MinimumBoundsSearch::new(config, by, max_carryover.unwrap_or(400)).run();
+
+    Ok(search.best.map(|(path, attempt)| {
+        let searched = attempt.bounds;
+        let number = GeneratedNumber { stopped_early: search.stop_reason.is_some(), ..path.into() };
+        (number, (searched.q(), searched.r(), searched.s()), attempt.carryover)
+    }))
+}
+
+/// Whether a pattern fits (`None` if the search stopped early), a pattern that does, and how many paths each pruning
+/// rule ruled out.
+type FeasibilityResult = (Option<bool>, Option<GeneratedNumber>, HashMap<String, usize>);
+
+#[pyfunction(kwargs = "**")]
+#[pyo3(name = "check_feasibility")]
+fn check_feasibility_py(target: PyRatio, kwargs: Option<&PyDict>) -> PyResult<FeasibilityResult> {
+    let config = config_from_kwargs(target, Algorithm::Beam, kwargs)?;
+    let bounds = config.bounds.unwrap_or(Bounds::from(8));
+    let report = FeasibilityCheck::new(config, bounds).run();
+
+    let pruned = report.pruned.into_iter().map(|(rule, count)| (rule.to_string(), count)).collect();
+    Ok(match report.feasibility {
+        Feasibility::Feasible(path) => (Some(true), Some(path.into()), pruned),
+        Feasibility::Infeasible => (Some(false), None, pruned),
+        Feasibility::Unknown(_) => (None, None, pruned),
+    })
+}
+
+#[pyclass(name = "PatternStream")]
+pub struct PyImprovements(Improvements);
+
+#[pymethods]
+impl PyImprovements {
+    fn __iter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    fn __next__(mut slf: PyRefMut<Self>, py: Python) -> Option<(GeneratedNumber, f64)> {
+        let improvements = &mut slf.0;
+        let improvement = py.allow_threads(|| improvements.next())?;
+        Some((improvement.path.into(), improvement.elapsed.as_secs_f64()))
+    }
+}
+
+#[pyfunction(kwargs = "**")]
+#[pyo3(name = "stream_number_patterns")]
+fn stream_number_patterns_py(
+    target: PyRatio,
+    algorithm: Option<&str>,
+    kwargs: Option<&PyDict>,
+) -> PyResult<PyImprovements> {
+    let algorithm = parse_algorithm(algorithm)?;
+    Ok(PyImprovements(Improvements::spawn(algorithm, config_from_kwargs(target, algorithm, kwargs)?)))
+}
+
+#[pyfunction]
+#[pyo3(name = "decode_number_pattern")]
+fn decode_number_pattern_py(py: Python, direction: &str, pattern: &str) -> PyResult<PyObject> {
+    let value = direction
+        .parse()
+        .and_then(|direction| decode_number_pattern(direction, pattern))
+        .map_err(|err| PyValueError::new_err(err.to_string()))?;
+
+    Ok(if value.is_integer() { value.to_integer().into_py(py) } else { (*value.numer(), *value.denom()).into_py(py) })
+}
+
+#[pyfunction]
+#[pyo3(name = "validate_pattern")]
+fn validate_pattern_py(
+    direction: &str,
+    pattern: &str,
+    q_size: Option<u32>,
+    r_size: Option<u32>,
+    s_size: Option<u32>,
+) -> PyResult<PyPatternReport> {
+    direction
+        .parse()
+        .and_then(|direction| {
+            validate_pattern(
+                direction,
+                pattern,
+                Bounds::new(q_size.unwrap_or(8), r_size.unwrap_or(8), s_size.unwrap_or(8)),
+            )
+        })
+        .map(Into::into)
+        .map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+#[pymodule]
+fn hexnumgen(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(generate_number_pattern_beam_py, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_number_pattern_astar_py, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_number_pattern_py, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_number_patterns_py, m)?)?;
+    m.add_function(wrap_pyfunction!(stream_number_patterns_py, m)?)?;
+    m.add_function(wrap_pyfunction!(find_minimum_bounds_py, m)?)?;
+    m.add_function(wrap_pyfunction!(check_feasibility_py, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_number_pattern_py, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_pattern_py, m)?)?;
+    m.add_class::<GeneratedNumber>()?;
+    m.add_class::<PyPatternReport>()?;
+    m.add_class::<PyImprovements>()?;
+    Ok(())
+}
@@ -1,5 +1,8 @@
+//! Helper traits for working with [`Ratio`]s.
+
 use num_rational::Ratio;
 
+/// Converts a signed ratio into its unsigned absolute value.
 pub trait UnsignedAbsRatio<UnsignedInt> {
     fn unsigned_abs(self) -> Ratio<UnsignedInt>;
 }
@@ -10,6 +13,7 @@ impl UnsignedAbsRatio<u64> for Ratio<i64> {
     }
 }
 
+/// Absolute difference of two unsigned ratios, without underflowing.
 pub trait AbsDiffRatio {
     fn abs_diff(self, other: Self) -> Self;
 }
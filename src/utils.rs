@@ -1,9 +1,8 @@
 use num_integer::Integer;
 use num_rational::Ratio;
 use num_traits::Signed;
-use std::collections::HashSet;
-use std::hash::Hash;
 
+/// Sign of a number pattern. Zero is drawn with the positive prefix.
 #[derive(Debug, Clone, Copy)]
 pub enum NonZeroSign {
     Positive,
@@ -32,25 +31,3 @@ where
         }
     }
 }
-
-pub fn cloned_push<T>(vec: &[T], item: T) -> Vec<T>
-where
-    T: Clone,
-{
-    vec.iter().chain(&[item]).cloned().collect()
-}
-
-pub fn cloned_union<T, U>(hashset: &HashSet<T>, iter: U) -> HashSet<T>
-where
-    T: Eq + Hash + Clone,
-    U: IntoIterator<Item = T>,
-{
-    hashset.union(&HashSet::from_iter(iter)).cloned().collect()
-}
-
-pub fn cloned_union_single<T>(hashset: &HashSet<T>, item: T) -> HashSet<T>
-where
-    T: Eq + Hash + Clone,
-{
-    cloned_union(hashset, [item])
-}